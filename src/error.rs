@@ -0,0 +1,46 @@
+use alloc::string::String;
+use core::fmt;
+
+/// Coefficient indices are 0-based, from the first coefficient given.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    Parse { index: usize, token: String },
+    DivisionByZero { index: usize, token: String },
+    NonFinite { index: usize },
+    TooManyCoefficients { count: usize },
+    DuplicatePoint { x: f64 },
+    NotRational32 { index: usize },
+    NanBound,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse { index, token } => {
+                write!(f, "coefficient {} ('{token}') is invalid", index + 1)
+            }
+            Self::DivisionByZero { index, token } => {
+                write!(f, "coefficient {} ('{token}') divides by zero", index + 1)
+            }
+            Self::NonFinite { index } => {
+                write!(f, "coefficient {} is not a finite number", index + 1)
+            }
+            Self::TooManyCoefficients { count } => {
+                write!(
+                    f,
+                    "too many coefficients ({count}), at most {} are supported",
+                    i32::MAX
+                )
+            }
+            Self::DuplicatePoint { x } => write!(f, "duplicate x value {x}"),
+            Self::NotRational32 { index } => write!(
+                f,
+                "coefficient {} is not a ratio of 32-bit integers",
+                index + 1
+            ),
+            Self::NanBound => write!(f, "an interval bound is NaN"),
+        }
+    }
+}
+
+impl core::error::Error for Error {}
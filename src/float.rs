@@ -1,5 +1,9 @@
 pub trait Float {
     fn near_zero(self) -> bool;
+    fn near_zero_within(self, tol: f64) -> bool;
+    /// Whether `self` is negligible next to a quantity of magnitude `scale`.
+    fn near_zero_rel(self, scale: f64) -> bool;
+    fn near_zero_rel_within(self, scale: f64, tol: f64) -> bool;
     fn negate(self) -> Self;
     fn ilog2f(self) -> i32;
 }
@@ -8,7 +12,19 @@ pub const TOLERANCE: f64 = 1e-15;
 
 impl Float for f64 {
     fn near_zero(self) -> bool {
-        -TOLERANCE < self && self < TOLERANCE
+        self.near_zero_within(TOLERANCE)
+    }
+
+    fn near_zero_within(self, tol: f64) -> bool {
+        -tol < self && self < tol
+    }
+
+    fn near_zero_rel(self, scale: f64) -> bool {
+        self.near_zero_rel_within(scale, TOLERANCE)
+    }
+
+    fn near_zero_rel_within(self, scale: f64, tol: f64) -> bool {
+        self.abs() <= tol * scale.abs()
     }
 
     fn negate(self) -> f64 {
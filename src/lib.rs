@@ -0,0 +1,12 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![feature(unboxed_closures, fn_traits, iter_map_windows)]
+#![cfg_attr(test, feature(test))]
+
+extern crate alloc;
+#[cfg(test)]
+extern crate test;
+
+pub mod error;
+pub mod float;
+pub mod polynomial;
+pub mod roots;
@@ -1,37 +1,196 @@
-#![feature(unboxed_closures, fn_traits, iter_intersperse, test)]
+#![feature(iter_intersperse)]
 
-extern crate test;
-
-mod float;
-mod polynomial;
-mod roots;
-
-use anyhow::Result;
-use polynomial::Polynomial;
-use roots::{find_roots, Root};
+use anyhow::{anyhow, Result};
+use polynomial_roots_calculator::{
+    error::Error,
+    polynomial::{parse_coefs, tokenize, Polynomial, SignInterval},
+    roots::{find_roots, find_roots_by_bisection, ComplexRoot, Root, RootFinder, Solution, Trace},
+};
 use std::{
+    cmp::Ordering,
     env,
     io::{self, prelude::*, IsTerminal},
 };
 
-fn parse_coefs(iter: impl DoubleEndedIterator<Item = impl AsRef<str>>) -> Result<Vec<f64>> {
-    iter.map(|v| v.as_ref().parse().map_err(anyhow::Error::new))
-        .rev()
-        .collect()
+#[derive(Debug, Default, PartialEq)]
+enum Method {
+    #[default]
+    Auto,
+    Bisection,
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Options {
+    ascending: bool,
+    verbose: bool,
+    complex: bool,
+    stdin_json: bool,
+    fractions: bool,
+    roots_only: bool,
+    check: bool,
+    method: Method,
+    plot_data: Option<(f64, f64, usize)>,
+    max_degree: Option<i32>,
+    seed: Option<u64>,
+    tolerance: Option<f64>,
+    precision: Option<usize>,
+}
+
+impl Options {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<(Self, Vec<String>)> {
+        let mut opts = Self::default();
+        let mut rest = vec![];
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--ascending" => opts.ascending = true,
+                "--verbose" => opts.verbose = true,
+                "--complex" => opts.complex = true,
+                "--stdin-json" => opts.stdin_json = true,
+                "--fractions" => opts.fractions = true,
+                "--roots-only" => opts.roots_only = true,
+                "--check" => opts.check = true,
+                "--method" => {
+                    opts.method = match flag_value::<String>(&arg, &mut args)?.as_str() {
+                        "auto" => Method::Auto,
+                        "bisection" => Method::Bisection,
+                        method => anyhow::bail!("unknown method {method}"),
+                    }
+                }
+                "--plot-data" => {
+                    opts.plot_data = Some((
+                        flag_value(&arg, &mut args)?,
+                        flag_value(&arg, &mut args)?,
+                        flag_value(&arg, &mut args)?,
+                    ))
+                }
+                "--seed" => opts.seed = Some(flag_value(&arg, &mut args)?),
+                "--tolerance" => opts.tolerance = Some(flag_value(&arg, &mut args)?),
+                "--max-degree" => opts.max_degree = Some(flag_value(&arg, &mut args)?),
+                "--precision" => opts.precision = Some(flag_value(&arg, &mut args)?),
+                flag if flag.starts_with("--") => anyhow::bail!("unknown flag {flag}"),
+                _ => rest.push(arg),
+            }
+        }
+
+        if opts.precision == Some(0) {
+            anyhow::bail!("--precision must be at least 1");
+        }
+
+        if opts.tolerance.is_some_and(|t| t.is_nan() || t <= 0.) {
+            anyhow::bail!("--tolerance must be positive");
+        }
+
+        Ok((opts, rest))
+    }
 }
 
-fn parse_stdin(stdin: &mut io::StdinLock) -> Result<Vec<f64>> {
-    let mut buf = String::new();
-    stdin.read_to_string(&mut buf)?;
+fn flag_value<T>(flag: &str, args: &mut impl Iterator<Item = String>) -> Result<T>
+where
+    T: std::str::FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    let value = args
+        .next()
+        .ok_or_else(|| anyhow!("{flag} requires a value"))?;
 
-    parse_coefs(buf.split_whitespace())
+    value
+        .parse()
+        .map_err(|err| anyhow::Error::new(err).context(format!("invalid value for {flag}")))
 }
 
-fn interactive_prompt(stdin: &mut io::StdinLock, stdout: &mut io::StdoutLock) -> Result<()> {
+const DEFAULT_MAX_DEGREE: i32 = 10_000;
+
+fn check_degree(p: &Polynomial, opts: &Options) -> Result<()> {
+    let max = opts.max_degree.unwrap_or(DEFAULT_MAX_DEGREE);
+    if p.grade() > max {
+        anyhow::bail!(
+            "the polynomial has degree {}, above the limit of {max} (see --max-degree)",
+            p.grade()
+        );
+    }
+
+    Ok(())
+}
+
+fn parse_block(block: &str, opts: &Options) -> Result<Vec<f64>> {
+    if opts.stdin_json || block.trim_start().starts_with('[') {
+        parse_json_coefs(block, opts.ascending)
+    } else {
+        Ok(parse_coefs(tokenize(block), opts.ascending)?)
+    }
+}
+
+// Blank lines separate polynomials, each one solved as soon as it has been read.
+fn solve_stream(input: impl BufRead, output: &mut impl Write, opts: &Options) -> Result<()> {
+    let mut block = String::new();
+    let mut solved_any = false;
+
+    for line in input.lines() {
+        let line = line?;
+
+        if !line.trim().is_empty() {
+            block += &line;
+            block += "\n";
+            continue;
+        }
+
+        if !block.is_empty() {
+            write_solution(&parse_block(&block, opts)?.into(), opts, output)?;
+            block.clear();
+            solved_any = true;
+        }
+    }
+
+    if !block.is_empty() || !solved_any {
+        write_solution(&parse_block(&block, opts)?.into(), opts, output)?;
+    }
+
+    Ok(())
+}
+
+fn parse_json_coefs(s: &str, ascending: bool) -> Result<Vec<f64>> {
+    let values: Vec<serde_json::Value> = serde_json::from_str(s)
+        .map_err(|err| anyhow::Error::new(err).context("input is not a JSON array"))?;
+
+    let mut coefs = values
+        .iter()
+        .enumerate()
+        .map(|(index, v)| {
+            v.as_f64().ok_or_else(|| Error::Parse {
+                index,
+                token: v.to_string(),
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if !ascending {
+        coefs.reverse();
+    }
+
+    Ok(coefs)
+}
+
+fn interactive_prompt(
+    stdin: &mut io::StdinLock,
+    stdout: &mut io::StdoutLock,
+    opts: &Options,
+) -> Result<()> {
+    let order = if opts.ascending {
+        "from the lowest to the highest"
+    } else {
+        "from the highest to the lowest"
+    };
+
     writeln!(stdout, "Welcome to the polynomial real roots calculator")?;
-    writeln!(stdout, "Please type in the coefficients, from the highest to the lowest monomial. Press Enter when ready.")?;
+    writeln!(
+        stdout,
+        "Please type in the coefficients, {order} monomial. Press Enter when ready."
+    )?;
+    writeln!(stdout, "Type \"history\" to list the polynomials entered so far, \"solve N\" to solve the Nth one again and \"factor N\" to factor it.")?;
 
     let mut input = String::new();
+    let mut history: Vec<Polynomial> = vec![];
 
     loop {
         write!(stdout, "> ")?;
@@ -40,32 +199,302 @@ fn interactive_prompt(stdin: &mut io::StdinLock, stdout: &mut io::StdoutLock) ->
         input.clear();
         stdin.read_line(&mut input)?;
 
-        if input.trim() == "exit" {
+        let command = input.trim();
+
+        if command == "exit" {
             writeln!(stdout, "Bye!")?;
             return Ok(());
         }
 
-        let coefs = match parse_coefs(input.split_whitespace()) {
-            Ok(res) => res,
-            Err(_) => {
-                writeln!(stdout, "\nInvalid input, please try again.")?;
+        if command == "history" {
+            if history.is_empty() {
+                writeln!(stdout, "\nNo polynomials entered yet.")?;
+            }
+
+            for (i, p) in history.iter().enumerate() {
+                writeln!(stdout, "{}: {}", i + 1, p)?;
+            }
+
+            continue;
+        }
+
+        if let Some(index) = command.strip_prefix("factor") {
+            match history_entry(&history, index) {
+                Some(p) => writeln!(stdout, "Factorization: {}", format_factorization(p))?,
+                None => writeln!(
+                    stdout,
+                    "\nNo polynomial at index {:?}, type \"history\" to see the available ones.",
+                    index.trim()
+                )?,
+            }
+
+            continue;
+        }
+
+        let p = if let Some(index) = command.strip_prefix("solve") {
+            match history_entry(&history, index) {
+                Some(p) => p.clone(),
+                None => {
+                    writeln!(
+                        stdout,
+                        "\nNo polynomial at index {:?}, type \"history\" to see the available ones.",
+                        index.trim()
+                    )?;
+                    continue;
+                }
+            }
+        } else {
+            let coefs = match parse_coefs(tokenize(&input), opts.ascending) {
+                Ok(res) => res,
+                Err(err) => {
+                    writeln!(stdout, "\nInvalid input ({err:#}), please try again.")?;
+                    continue;
+                }
+            };
+            let p: Polynomial = coefs.into();
+            if let Err(err) = check_degree(&p, opts) {
+                writeln!(stdout, "\nInvalid input ({err:#}), please try again.")?;
                 continue;
             }
+            history.push(p.clone());
+
+            p
         };
-        let p: Polynomial = coefs.into();
+
+        let (positive, negative) = p.descartes_bounds();
+        let (solution, trace) = solve(&p, opts);
 
         writeln!(
             stdout,
-            "Polynomial: {}\nDerivative: {}\nRoot bound: {}\nRoots: {}\n\nInput coefficients or \"exit\" to close the program.",
+            "Polynomial: {}\nDerivative: {}\nRoot bound: {}\nDescartes' rule: at most {} positive and {} negative roots\nRoots: {}\n\nInput coefficients, \"history\", \"solve N\", \"factor N\" or \"exit\" to close the program.",
             p,
             p.derivative(),
             p.root_bound().map_or("none".into(), |v| format!("±{v} (approx.)")),
-            format_output_interactive(find_roots(&p).as_deref())
+            positive,
+            negative,
+            format_output_interactive(solution.as_ref().map(|s| &s.roots[..]), opts)
         )?;
+
+        if opts.complex {
+            writeln!(
+                stdout,
+                "Complex roots: {}",
+                format_complex_interactive(
+                    finder(opts).solve_complex(&p).as_deref(),
+                    opts.precision
+                )
+            )?;
+        }
+
+        if solution.as_ref().is_some_and(|s| !s.did_converge) {
+            writeln!(
+                stdout,
+                "Warning: the approximation did not fully converge, the roots may be inaccurate."
+            )?;
+        }
+
+        if opts.check && !opts.verbose {
+            writeln!(stdout, "Check:")?;
+            for root in solution.iter().flat_map(|s| &s.roots) {
+                writeln!(stdout, "  {}", format_residual(&p, root.value, opts))?;
+            }
+        }
+
+        if opts.verbose {
+            writeln!(stdout, "Steps:")?;
+            for step in trace.steps() {
+                writeln!(stdout, "  {step}")?;
+            }
+
+            for root in solution.iter().flat_map(|s| &s.roots) {
+                writeln!(
+                    stdout,
+                    "  synthetic division by x-({}): {}",
+                    format_value(root.value, opts.precision),
+                    format_horner_table(&p.horner_table(root.value), opts.precision)
+                )?;
+                writeln!(stdout, "  {}", format_residual(&p, root.value, opts))?;
+            }
+
+            for row in p.sign_table(&p.isolate_roots()) {
+                writeln!(stdout, "  {}", format_sign_interval(&row, opts.precision))?;
+            }
+        }
+    }
+}
+
+fn history_entry<'a>(history: &'a [Polynomial], index: &str) -> Option<&'a Polynomial> {
+    let index = index.trim().parse::<usize>().ok()?;
+    history.get(index.checked_sub(1)?)
+}
+
+fn format_factorization(p: &Polynomial) -> String {
+    if p.grade() <= 0 {
+        return p.to_string();
+    }
+
+    let mut factors = vec![];
+
+    for (f, multiplicity) in p.squarefree_factorization() {
+        let mut rest = f.clone();
+
+        for root in find_roots(&f).unwrap_or_default() {
+            let linear = Polynomial::from_roots(&[(root.value, 1)]);
+            let (quotient, remainder) = rest.div_rem(&linear);
+
+            if remainder == Polynomial::ZERO {
+                factors.push((linear, multiplicity));
+                rest = quotient;
+            }
+        }
+
+        if rest.grade() > 0 {
+            factors.push((rest, multiplicity));
+        }
+    }
+
+    let constant = factors
+        .iter()
+        .fold(p.lead(), |c, (f, m)| c / f.lead().powi(*m));
+
+    let factors = factors.iter().map(|(f, m)| match m {
+        1 => format!("({f})"),
+        _ => format!("({f})^{m}"),
+    });
+
+    if constant == 1. {
+        factors.intersperse(" ".into()).collect()
+    } else {
+        std::iter::once(constant.to_string())
+            .chain(factors)
+            .intersperse(" ".into())
+            .collect()
+    }
+}
+
+fn format_horner_table(table: &[f64], precision: Option<usize>) -> String {
+    let Some((rem, quotient)) = table.split_last() else {
+        return "0".into();
+    };
+
+    let quotient: String = quotient
+        .iter()
+        .map(|&v| format_value(v, precision))
+        .intersperse(" ".into())
+        .collect();
+
+    format!("{quotient} | {}", format_value(*rem, precision))
+}
+
+fn format_residual(p: &Polynomial, root: f64, opts: &Options) -> String {
+    let residual = p.eval_compensated(root).abs();
+    format!(
+        "|p({})| = {residual:e}{}",
+        format_value(root, opts.precision),
+        if p.is_root_within(root, finder(opts).tolerance) {
+            ""
+        } else {
+            " (exceeds tolerance)"
+        }
+    )
+}
+
+fn format_sign_interval(row: &SignInterval, precision: Option<usize>) -> String {
+    let sign = |o: Ordering| match o {
+        Ordering::Less => "-",
+        Ordering::Equal => "0",
+        Ordering::Greater => "+",
+    };
+
+    format!(
+        "sign of p on ({}, {}]: {} to {}, root near {}",
+        format_value(row.a, precision),
+        format_value(row.b, precision),
+        sign(row.sign_a),
+        sign(row.sign_b),
+        format_value(row.root, precision)
+    )
+}
+
+fn finder(opts: &Options) -> RootFinder {
+    let default = RootFinder::default();
+    RootFinder {
+        seed: opts.seed,
+        tolerance: opts.tolerance.unwrap_or(default.tolerance),
+        ..default
+    }
+}
+
+fn solve(p: &Polynomial, opts: &Options) -> (Option<Solution>, Trace) {
+    let mut trace = if opts.verbose {
+        Trace::enabled()
+    } else {
+        Trace::default()
+    };
+
+    let solution = match opts.method {
+        Method::Auto => finder(opts).solve_traced(p, &mut trace),
+        Method::Bisection => find_roots_by_bisection(p).map(|roots| Solution {
+            roots,
+            did_converge: true,
+        }),
+    };
+
+    (solution, trace)
+}
+
+fn format_value(v: f64, precision: Option<usize>) -> String {
+    match precision {
+        // Rounding through the exponential notation keeps `precision` significant digits.
+        Some(p) => format!("{v:.*e}", p - 1)
+            .parse::<f64>()
+            .unwrap()
+            .to_string(),
+        None => v.to_string(),
+    }
+}
+
+const MAX_FRACTION_DENOMINATOR: i64 = 1000;
+
+// Finds the fraction through the convergents of the continued fraction of v.
+fn format_fraction(v: f64) -> Option<String> {
+    let (mut h, mut h_prev, mut k, mut k_prev) = (1i64, 0i64, 0i64, 1i64);
+    let mut x = v.abs();
+
+    while k <= MAX_FRACTION_DENOMINATOR {
+        let a = x.floor();
+        if a > i64::MAX as f64 / 2. {
+            return None;
+        }
+
+        (h, h_prev) = (a as i64 * h + h_prev, h);
+        (k, k_prev) = (a as i64 * k + k_prev, k);
+
+        if k > 1
+            && k <= MAX_FRACTION_DENOMINATOR
+            && (h as f64 / k as f64 - v.abs()).abs() <= 1e-12 * v.abs()
+        {
+            let sign = if v < 0. { "-" } else { "" };
+            return Some(format!("{sign}{h}/{k}"));
+        }
+
+        if x == a {
+            return None;
+        }
+        x = 1. / (x - a);
     }
+
+    None
 }
 
-fn format_output_interactive(roots: Option<&[Root]>) -> String {
+fn format_root(v: f64, opts: &Options) -> String {
+    opts.fractions
+        .then(|| format_fraction(v))
+        .flatten()
+        .unwrap_or_else(|| format_value(v, opts.precision))
+}
+
+fn format_output_interactive(roots: Option<&[Root]>, opts: &Options) -> String {
     match roots {
         None => "Real roots: zero polynomial".into(),
         Some([]) => "Real roots: none".into(),
@@ -74,7 +503,7 @@ fn format_output_interactive(roots: Option<&[Root]>) -> String {
             .map(|r| {
                 format!(
                     "{}{}",
-                    r.value,
+                    format_root(r.value, opts),
                     if r.multiplicity > 1 {
                         format!(" (mul. {})", r.multiplicity)
                     } else {
@@ -87,32 +516,541 @@ fn format_output_interactive(roots: Option<&[Root]>) -> String {
     }
 }
 
-fn format_output_noninteractive(roots: Option<&[Root]>) -> String {
+fn format_output_noninteractive(roots: Option<&[Root]>, opts: &Options) -> String {
+    if opts.roots_only {
+        return roots
+            .unwrap_or_default()
+            .iter()
+            .map(|r| format_root(r.value, opts))
+            .intersperse("\n".into())
+            .collect();
+    }
+
     match roots {
         None => "zero".into(),
         Some([]) => "none".into(),
         Some(roots) => roots
             .iter()
-            .map(|r| format!("{}:{}", r.value, r.multiplicity))
+            .map(|r| format!("{}:{}", format_root(r.value, opts), r.multiplicity))
+            .intersperse(" ".into())
+            .collect(),
+    }
+}
+
+fn format_complex_interactive(roots: Option<&[ComplexRoot]>, precision: Option<usize>) -> String {
+    match roots {
+        None => "zero polynomial".into(),
+        Some([]) => "none".into(),
+        // Roots come in conjugate pairs, the one with the positive imaginary part first.
+        Some(roots) => roots
+            .chunks(2)
+            .map(|pair| {
+                let ComplexRoot {
+                    value,
+                    multiplicity,
+                } = pair[0];
+                let im = match format_value(value.im, precision) {
+                    im if im == "1" => "i".into(),
+                    im => format!("{im}i"),
+                };
+                format!(
+                    "{}{}",
+                    match value.re {
+                        0. => format!("±{im}"),
+                        re => format!("{} ± {im}", format_value(re, precision)),
+                    },
+                    if multiplicity > 1 {
+                        format!(" (mul. {multiplicity})")
+                    } else {
+                        "".into()
+                    }
+                )
+            })
+            .intersperse(", ".into())
+            .collect(),
+    }
+}
+
+fn format_complex_noninteractive(
+    roots: Option<&[ComplexRoot]>,
+    precision: Option<usize>,
+) -> String {
+    match roots {
+        None => "zero".into(),
+        Some([]) => "none".into(),
+        Some(roots) => roots
+            .iter()
+            .map(|r| {
+                format!(
+                    "{}:{}:{}",
+                    format_value(r.value.re, precision),
+                    format_value(r.value.im, precision),
+                    r.multiplicity
+                )
+            })
             .intersperse(" ".into())
             .collect(),
     }
 }
 
 fn main() -> Result<()> {
-    let args = env::args();
+    let (opts, args) = Options::parse(env::args().skip(1))?;
 
-    let coefs;
-    if args.len() > 1 {
-        coefs = parse_coefs(args.skip(1))?;
-    } else if !io::stdin().is_terminal() {
-        coefs = parse_stdin(&mut io::stdin().lock())?;
-    } else {
-        return interactive_prompt(&mut io::stdin().lock(), &mut io::stdout().lock());
+    if args.is_empty() && !io::stdin().is_terminal() {
+        return solve_stream(io::stdin().lock(), &mut io::stdout().lock(), &opts);
+    } else if args.is_empty() {
+        return interactive_prompt(&mut io::stdin().lock(), &mut io::stdout().lock(), &opts);
+    }
+
+    let p: Polynomial = parse_coefs(tokenize(&args.join(" ")), opts.ascending)?.into();
+    write_solution(&p, &opts, &mut io::stdout().lock())
+}
+
+fn write_solution(p: &Polynomial, opts: &Options, output: &mut impl Write) -> Result<()> {
+    check_degree(p, opts)?;
+
+    if let Some((a, b, n)) = opts.plot_data {
+        return write_plot_data(p, a, b, n, opts, output);
+    }
+
+    let (solution, trace) = solve(p, opts);
+
+    if opts.verbose {
+        eprintln!(
+            "root bound: {}",
+            p.root_bound().map_or("none".into(), |v| format!("±{v}"))
+        );
+        trace.steps().iter().for_each(|step| eprintln!("{step}"));
+        for root in solution
+            .iter()
+            .flat_map(|s| &s.roots)
+            .filter(|_| !opts.check)
+        {
+            eprintln!("{}", format_residual(p, root.value, opts));
+        }
+    }
+
+    if solution.as_ref().is_some_and(|s| !s.did_converge) {
+        eprintln!("warning: the approximation did not fully converge");
+    }
+
+    let roots = format_output_noninteractive(solution.as_ref().map(|s| &s.roots[..]), opts);
+    if !roots.is_empty() {
+        writeln!(output, "{roots}")?;
+    }
+
+    if opts.complex {
+        writeln!(
+            output,
+            "{}",
+            format_complex_noninteractive(finder(opts).solve_complex(p).as_deref(), opts.precision)
+        )?;
+    }
+
+    if opts.check {
+        for root in solution.iter().flat_map(|s| &s.roots) {
+            writeln!(output, "{}", format_residual(p, root.value, opts))?;
+        }
+    }
+
+    Ok(())
+}
+
+fn write_plot_data(
+    p: &Polynomial,
+    a: f64,
+    b: f64,
+    n: usize,
+    opts: &Options,
+    output: &mut impl Write,
+) -> Result<()> {
+    writeln!(output, "x,y")?;
+    for (x, y) in p.sample(a, b, n) {
+        writeln!(
+            output,
+            "{},{}",
+            format_value(x, opts.precision),
+            format_value(y, opts.precision)
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use polynomial_roots_calculator::{polynomial::parse_coef, roots::find_complex_roots};
+
+    fn args(s: &str) -> impl Iterator<Item = String> + '_ {
+        s.split_whitespace().map(String::from)
+    }
+
+    #[test]
+    fn test_ascending() {
+        let (opts, rest) = Options::parse(args("--ascending 1 2 3")).unwrap();
+        assert!(opts.ascending);
+
+        let (default, default_rest) = Options::parse(args("3 2 1")).unwrap();
+        assert!(!default.ascending);
+
+        assert_eq!(
+            parse_coefs(rest.iter(), opts.ascending).unwrap(),
+            parse_coefs(default_rest.iter(), default.ascending).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_separators() {
+        let expected = [2., -3., 1.];
+        assert_eq!(parse_coefs(tokenize("1,-3,2"), false).unwrap(), expected);
+        assert_eq!(parse_coefs(tokenize("1, -3 2"), false).unwrap(), expected);
+        assert_eq!(parse_coefs(tokenize("1; -3;2;"), false).unwrap(), expected);
+        assert_eq!(
+            parse_coefs(tokenize(" 1, -3, 2,\n"), false).unwrap(),
+            expected
+        );
+        assert_eq!(parse_coefs(tokenize("1,,"), false).unwrap(), [1.]);
+    }
+
+    #[test]
+    fn test_fractions() {
+        assert_eq!(parse_coef("1/4").unwrap(), 0.25);
+        assert_eq!(parse_coef("-3/4").unwrap(), -0.75);
+        assert_eq!(parse_coef("2").unwrap(), 2.);
+        assert_eq!(
+            parse_coefs(args("1/2 -3/4 1"), false).unwrap(),
+            [1., -0.75, 0.5]
+        );
+
+        assert!(parse_coef("1/0").is_none());
+        assert!(parse_coef("1/x").is_none());
+
+        let err = parse_coefs(args("1 1/0"), true).unwrap_err();
+        assert_eq!(
+            err,
+            Error::DivisionByZero {
+                index: 1,
+                token: "1/0".into()
+            }
+        );
+        assert_eq!(err.to_string(), "coefficient 2 ('1/0') divides by zero");
+        assert!(matches!(
+            parse_coefs(args("1/x"), true),
+            Err(Error::Parse { .. })
+        ));
+    }
+
+    #[test]
+    fn test_non_finite() {
+        let err = parse_coefs(args("1 1e400 2"), false).unwrap_err();
+        assert_eq!(err.to_string(), "coefficient 2 is not a finite number");
+
+        assert!(parse_coefs(args("1 -1e400"), false).is_err());
+        assert!(parse_coefs(args("NaN"), false).is_err());
+        assert!(parse_coefs(args("1e300/1e-300"), false).is_err());
+
+        let err = parse_coefs(args("1 x"), false).unwrap_err();
+        assert!(format!("{err:#}").starts_with("coefficient 2 ('x') is invalid"));
+    }
+
+    #[test]
+    fn test_precision() {
+        use std::f64::consts::SQRT_2;
+
+        let (opts, _) = Options::parse(args("--precision 3 1 2")).unwrap();
+        assert_eq!(opts.precision, Some(3));
+        assert_eq!(format_value(SQRT_2, opts.precision), "1.41");
+        assert_eq!(format_value(-12345.6, opts.precision), "-12300");
+        assert_eq!(format_value(0.5, opts.precision), "0.5");
+        assert_eq!(format_value(SQRT_2, None), "1.4142135623730951");
+
+        let roots = [Root {
+            value: SQRT_2,
+            multiplicity: 2,
+        }];
+        assert_eq!(format_output_noninteractive(Some(&roots), &opts), "1.41:2");
+        assert_eq!(
+            format_output_interactive(Some(&roots), &opts),
+            "1.41 (mul. 2)"
+        );
+
+        assert!(Options::parse(args("--precision")).is_err());
+        assert!(Options::parse(args("--precision x")).is_err());
+        assert!(Options::parse(args("--precision 0")).is_err());
+    }
+
+    #[test]
+    fn test_format_factorization() {
+        let p = Polynomial::from_roots(&[(1., 2), (-3., 1)]);
+        assert_eq!(format_factorization(&p), "(x+3) (x-1)^2");
+
+        let p = Polynomial::from([-4., 0., 2.]) * Polynomial::from([1., 1.]).pow(2);
+        assert_eq!(format_factorization(&p), "2 (x^2-2) (x+1)^2");
+
+        assert_eq!(format_factorization(&[2., -1.].into()), "-1 (x-2)");
+        assert_eq!(format_factorization(&[5.].into()), "5");
+    }
+
+    #[test]
+    fn test_complex() {
+        let (opts, _) = Options::parse(args("--complex 1 0 1")).unwrap();
+        assert!(opts.complex);
+
+        let roots = find_complex_roots(&[1., 0., 1.].into());
+        assert_eq!(format_complex_interactive(roots.as_deref(), None), "±i");
+        assert_eq!(
+            format_complex_noninteractive(roots.as_deref(), None),
+            "0:1:1 0:-1:1"
+        );
+
+        // x^2 - 2x + 5 has roots 1 ± 2i.
+        let roots = find_complex_roots(&[5., -2., 1.].into());
+        assert_eq!(
+            format_complex_interactive(roots.as_deref(), Some(6)),
+            "1 ± 2i"
+        );
+        assert_eq!(
+            format_complex_noninteractive(roots.as_deref(), Some(6)),
+            "1:2:1 1:-2:1"
+        );
+
+        let roots = find_complex_roots(&[1., 2., 1.].into());
+        assert_eq!(format_complex_interactive(roots.as_deref(), None), "none");
+    }
+
+    #[test]
+    fn test_json_input() {
+        let (opts, _) = Options::parse(args("--stdin-json")).unwrap();
+        assert!(opts.stdin_json);
+
+        let p: Polynomial = parse_json_coefs("[1,-3,2]", false).unwrap().into();
+        assert_eq!(
+            format_output_noninteractive(find_roots(&p).as_deref(), &Options::default()),
+            "1:1 2:1"
+        );
+        assert_eq!(
+            parse_json_coefs(" [2, -3, 1]\n", true).unwrap(),
+            parse_json_coefs("[1, -3, 2]", false).unwrap()
+        );
+
+        assert_eq!(
+            parse_json_coefs("[1, 2, \"abc\"]", false)
+                .unwrap_err()
+                .to_string(),
+            "coefficient 3 ('\"abc\"') is invalid"
+        );
+        assert_eq!(
+            parse_json_coefs("[null]", false).unwrap_err().to_string(),
+            "coefficient 1 ('null') is invalid"
+        );
+        assert!(parse_json_coefs("1 -3 2", false).is_err());
+    }
+
+    #[test]
+    fn test_format_horner_table() {
+        let p: Polynomial = [6., -5., 1.].into();
+        assert_eq!(format_horner_table(&p.horner_table(2.), None), "1 -3 | 0");
+        assert_eq!(format_horner_table(&[], None), "0");
+    }
+
+    #[test]
+    fn test_format_sign_interval() {
+        let p = Polynomial::from([-1., 0., 1.]);
+        let rows = p.sign_table(&p.isolate_roots());
+        assert_eq!(
+            format_sign_interval(&rows[1], Some(3)),
+            format!(
+                "sign of p on ({}, {}]: - to +, root near 1",
+                format_value(rows[1].a, Some(3)),
+                format_value(rows[1].b, Some(3))
+            )
+        );
+    }
+
+    #[test]
+    fn test_solve_stream() {
+        let solve = |input: &str| {
+            let mut output = vec![];
+            solve_stream(input.as_bytes(), &mut output, &Options::default()).unwrap();
+            String::from_utf8(output).unwrap()
+        };
+
+        assert_eq!(solve("1 -3 2\n\n  \n1 0 -4\n"), "1:1 2:1\n-2:1 2:1\n");
+        assert_eq!(solve("1 -3\n2\n"), "1:1 2:1\n");
+        assert_eq!(solve("[1, -3, 2]\n\n1 -1\n\n"), "1:1 2:1\n1:1\n");
+        assert_eq!(solve(""), "zero\n");
+    }
+
+    #[test]
+    fn test_fractions_output() {
+        let (opts, _) = Options::parse(args("--fractions")).unwrap();
+        assert!(opts.fractions);
+
+        let roots = find_roots(&[-1., 2.].into());
+        assert_eq!(
+            format_output_noninteractive(roots.as_deref(), &opts),
+            "1/2:1"
+        );
+        assert_eq!(
+            format_output_noninteractive(roots.as_deref(), &Options::default()),
+            "0.5:1"
+        );
+
+        // 3x^2 + x - 2 = (3x - 2)(x + 1)
+        let roots = find_roots(&[-2., 1., 3.].into());
+        assert_eq!(
+            format_output_interactive(roots.as_deref(), &opts),
+            "-1, 2/3"
+        );
+
+        assert_eq!(format_fraction(-0.75).as_deref(), Some("-3/4"));
+        assert_eq!(format_fraction(std::f64::consts::SQRT_2), None);
+        assert_eq!(format_fraction(3.), None);
+    }
+
+    #[test]
+    fn test_roots_only() {
+        let (opts, _) = Options::parse(args("--roots-only")).unwrap();
+        assert!(opts.roots_only);
+
+        let solve = |p: &Polynomial| {
+            let mut output = vec![];
+            write_solution(p, &opts, &mut output).unwrap();
+            String::from_utf8(output).unwrap()
+        };
+
+        assert_eq!(solve(&[4., -4., 1.].into()), "2\n");
+        assert_eq!(solve(&[-2., -1., 1.].into()), "-1\n2\n");
+        assert_eq!(solve(&[1., 0., 1.].into()), "");
+        assert_eq!(solve(&Polynomial::ZERO), "");
+    }
+
+    #[test]
+    fn test_method() {
+        let (opts, _) = Options::parse(args("--method bisection 1 0 -2")).unwrap();
+        assert_eq!(opts.method, Method::Bisection);
+        assert!(Options::parse(args("--method newton")).is_err());
+
+        let roots = solve(&[-2., 0., 1.].into(), &opts).0.unwrap().roots;
+        assert_eq!(roots.len(), 2);
+        assert!((roots[1].value - std::f64::consts::SQRT_2).abs() <= 2. * f64::EPSILON);
+    }
+
+    #[test]
+    fn test_plot_data() {
+        let (opts, rest) = Options::parse(args("--plot-data -1 1 3 1 0 -1")).unwrap();
+        assert_eq!(opts.plot_data, Some((-1., 1., 3)));
+        assert_eq!(rest, ["1", "0", "-1"]);
+        assert!(Options::parse(args("--plot-data 0 1")).is_err());
+        assert!(Options::parse(args("--plot-data 0 1 -2")).is_err());
+
+        let mut output = vec![];
+        write_solution(&[-1., 0., 1.].into(), &opts, &mut output).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "x,y\n-1,0\n0,-1\n1,0\n");
+    }
+
+    #[test]
+    fn test_max_degree() {
+        let (opts, _) = Options::parse(args("--max-degree 2")).unwrap();
+        assert_eq!(opts.max_degree, Some(2));
+        assert!(Options::parse(args("--max-degree x")).is_err());
+
+        let mut output = vec![];
+        write_solution(&[-2., -1., 1.].into(), &opts, &mut output).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "-1:1 2:1\n");
+
+        let mut output = vec![];
+        let err = write_solution(&[0., -2., -1., 1.].into(), &opts, &mut output).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "the polynomial has degree 3, above the limit of 2 (see --max-degree)"
+        );
+        assert!(output.is_empty());
+
+        let p: Polynomial = vec![1.; 20].into();
+        assert!(check_degree(&p, &Options::default()).is_ok());
+    }
+
+    #[test]
+    fn test_seed() {
+        let (opts, _) = Options::parse(args("--seed 7")).unwrap();
+        assert_eq!(opts.seed, Some(7));
+        assert!(Options::parse(args("--seed -1")).is_err());
+
+        let p = Polynomial::from_roots(&[(-1., 1), (0.5, 1), (3., 1), (4., 1), (9., 1)]);
+        let run = || {
+            let mut output = vec![];
+            write_solution(&p, &opts, &mut output).unwrap();
+            String::from_utf8(output).unwrap()
+        };
+        assert_eq!(run(), run());
     }
 
-    Ok(println!(
-        "{}",
-        format_output_noninteractive(find_roots(&coefs.into()).as_deref())
-    ))
+    #[test]
+    fn test_tolerance() {
+        let (opts, _) = Options::parse(args("--tolerance 1e-12")).unwrap();
+        assert_eq!(opts.tolerance, Some(1e-12));
+        assert_eq!(finder(&opts).tolerance, 1e-12);
+        assert_eq!(
+            finder(&Options::default()).tolerance,
+            RootFinder::default().tolerance
+        );
+
+        // x^3 - 8e-12 with noise in the linear term is only solved as a binomial once the
+        // tolerance covers the noise.
+        let p = Polynomial::from([-8e-12, 1e-17, 0., 1.]);
+        let run = |opts: &Options| {
+            let mut output = vec![];
+            write_solution(&p, opts, &mut output).unwrap();
+            String::from_utf8(output).unwrap()
+        };
+        let (loose, _) = Options::parse(args("--tolerance 1e-6")).unwrap();
+        assert_eq!(run(&loose).trim(), "0.0002000000000000001:1");
+        assert_ne!(run(&Options::default()), run(&loose));
+
+        assert!(Options::parse(args("--tolerance 0")).is_err());
+        assert!(Options::parse(args("--tolerance -1")).is_err());
+        assert!(Options::parse(args("--tolerance NaN")).is_err());
+    }
+
+    #[test]
+    fn test_check() {
+        let (opts, _) = Options::parse(args("--check")).unwrap();
+        assert!(opts.check);
+
+        let p = Polynomial::from_roots(&[(-3., 1), (0.5, 2), (2f64.sqrt(), 1), (7., 1)]);
+        let mut output = vec![];
+        write_solution(&p, &opts, &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<_> = output.lines().skip(1).collect();
+        assert_eq!(lines.len(), 4);
+        for line in lines {
+            let residual: f64 = line.split(" = ").nth(1).unwrap().parse().unwrap();
+            assert!(residual < 1e-12, "{line}");
+        }
+
+        let p = Polynomial::from([-2., 0., 1.]);
+        let (rounded, _) = Options::parse(args("--precision 3")).unwrap();
+        assert_eq!(
+            format_residual(&p, 2f64.sqrt(), &rounded)
+                .split(" = ")
+                .next(),
+            Some("|p(1.41)|")
+        );
+        assert!(format_residual(&p, 1.5, &opts).ends_with(" (exceeds tolerance)"));
+
+        // The residual at √2 + 1e-5 is about 1e-5 of the terms: within --tolerance 1e-4, but not
+        // within the default.
+        let x = 2f64.sqrt() + 1e-5;
+        assert!(format_residual(&p, x, &opts).ends_with(" (exceeds tolerance)"));
+        let (loose, _) = Options::parse(args("--check --tolerance 1e-4")).unwrap();
+        assert!(!format_residual(&p, x, &loose).ends_with(" (exceeds tolerance)"));
+    }
+
+    #[test]
+    fn test_unknown_flag() {
+        assert!(Options::parse(args("--descending 1 2")).is_err());
+        assert_eq!(Options::parse(args("-1 2")).unwrap().1, ["-1", "2"]);
+    }
 }
@@ -1,29 +1,159 @@
-use std::{
-    fmt::{self, Write},
-    ops::Index,
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    iter::{Enumerate, Map, Product, Sum},
+    ops::{Add, Div, Index, Mul, Neg, Rem, Sub},
+    slice,
+    str::FromStr,
 };
 
-use num_rational::Rational32;
-use num_traits::FromPrimitive;
+use num_complex::Complex64;
+use num_rational::{BigRational, Rational32};
+#[cfg(not(any(feature = "std", test)))]
+use num_traits::Float as _;
+use num_traits::{FromPrimitive, One, Signed, ToPrimitive, Zero};
+
+use crate::{
+    error::Error,
+    float::{Float, TOLERANCE},
+    roots::{
+        balanced_companion, bisect_root, companion_roots, deflation_roots, jenkins_traub_roots,
+        reciprocal_roots, ComplexRoot, Root,
+    },
+};
 
-use crate::float::Float;
+const SIGN_CHANGE_SAMPLES: usize = 64;
+const MODULUS: u64 = 2_147_483_647;
 
+/// Coefficients are always finite, so equality is reflexive and `Eq` and `Hash` can be
+/// implemented: two polynomials are equal, and hash alike, when their coefficients are exactly
+/// equal. No rounding is applied, so polynomials that only agree approximately are different
+/// keys; the only canonicalization is that `-0.0` and `0.0` are the same.
 #[derive(Debug, Clone, PartialEq)]
 pub struct Polynomial(Vec<f64>);
 
+impl Eq for Polynomial {}
+
+impl Hash for Polynomial {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Adding 0 turns -0 into 0, leaving every other value unchanged.
+        self.0.len().hash(state);
+        self.0.iter().for_each(|v| (v + 0.).to_bits().hash(state));
+    }
+}
+
 impl Polynomial {
     pub const ZERO: Self = Self(vec![]);
 
+    pub fn builder() -> PolynomialBuilder {
+        PolynomialBuilder::default()
+    }
+
+    /// The monic polynomial with the given roots and multiplicities. Panics if a multiplicity is
+    /// negative.
+    pub fn from_roots(roots: &[(f64, i32)]) -> Self {
+        roots
+            .iter()
+            .map(|&(r, m)| {
+                let m = u32::try_from(m).expect("multiplicities are not negative");
+                Self::from([r.negate(), 1.]).pow(m)
+            })
+            .product()
+    }
+
+    pub fn interpolate(points: &[(f64, f64)]) -> Result<Self, Error> {
+        for (i, (x, _)) in points.iter().enumerate() {
+            if points[..i].iter().any(|(other, _)| other == x) {
+                return Err(Error::DuplicatePoint { x: *x });
+            }
+        }
+
+        Ok(points
+            .iter()
+            .enumerate()
+            .fold(Self::ZERO, |acc, (i, &(xi, yi))| {
+                let basis = points
+                    .iter()
+                    .enumerate()
+                    .filter(|&(j, _)| j != i)
+                    .fold(Self::from([yi]), |acc, (_, &(xj, _))| {
+                        acc * Self::from([-xj / (xi - xj), 1. / (xi - xj)])
+                    });
+
+                acc + basis
+            }))
+    }
+
+    /// The fallible counterpart of `From`, which panics on the same inputs. `TryFrom` can't be
+    /// implemented separately, as it is already derived from `From`.
+    pub fn try_from_coefficients(value: impl Into<Vec<f64>>) -> Result<Self, Error> {
+        let v: Vec<_> = value.into();
+
+        if v.len() > i32::MAX as usize {
+            return Err(Error::TooManyCoefficients { count: v.len() });
+        }
+
+        if let Some(index) = v.iter().position(|v| !v.is_finite()) {
+            return Err(Error::NonFinite { index });
+        }
+
+        Ok(Self::normalize(v))
+    }
+
+    /// Drops trailing zero coefficients, so that a nonzero polynomial's lead is never zero.
+    fn normalize(mut v: Vec<f64>) -> Self {
+        while v.last() == Some(&0.) {
+            v.pop();
+        }
+
+        Self(v)
+    }
+
+    /// The coefficients, from the constant term up to the leading one, which is never zero.
+    pub fn coefficients(&self) -> &[f64] {
+        &self.0
+    }
+
+    /// Same ordering as `coefficients`.
+    pub fn into_coefficients(self) -> Vec<f64> {
+        self.0
+    }
+
     pub fn iter(&self) -> impl ExactSizeIterator + DoubleEndedIterator<Item = (i32, f64)> + '_ {
         self.0.iter().enumerate().map(|(i, &v)| (i as i32, v))
     }
 
+    pub fn terms(&self) -> impl DoubleEndedIterator<Item = (i32, f64)> + '_ {
+        self.iter().filter(|&(_, v)| v != 0.)
+    }
+
+    pub fn terms_desc(&self) -> impl Iterator<Item = (i32, f64)> + '_ {
+        self.terms().rev()
+    }
+
     pub fn grade(&self) -> i32 {
         (self.0.len() as i32) - 1
     }
 
     pub fn derivative(&self) -> Self {
-        Self(self.iter().skip(1).map(|(i, v)| (i as f64) * v).collect())
+        Self::normalize(self.iter().skip(1).map(|(i, v)| (i as f64) * v).collect())
+    }
+
+    pub fn derivative_n(&self, order: u32) -> Self {
+        let n = order as i32;
+
+        Self::normalize(
+            self.iter()
+                .skip(order as usize)
+                .map(|(i, v)| ((i - n + 1)..=i).fold(v, |acc, k| acc * k as f64))
+                .collect(),
+        )
     }
 
     pub fn div_rem(&self, rhs: &Self) -> (Self, Self) {
@@ -31,17 +161,216 @@ impl Polynomial {
         (Polynomial::from_ratios(res), Polynomial::from_ratios(rem))
     }
 
+    pub fn quotient(&self, rhs: &Self) -> Self {
+        self.div_rem(rhs).0
+    }
+
+    pub fn remainder(&self, rhs: &Self) -> Self {
+        self.div_rem(rhs).1
+    }
+
+    /// Whether `other` is an exact multiple of `self`. The zero polynomial divides nothing.
+    pub fn divides(&self, other: &Self) -> bool {
+        self.grade() >= 0 && other.div_rem(self).1 == Self::ZERO
+    }
+
+    pub fn l1_norm(&self) -> f64 {
+        self.0.iter().map(|v| v.abs()).sum()
+    }
+
+    pub fn l2_norm(&self) -> f64 {
+        // Scaling by the largest coefficient keeps the squares from overflowing.
+        let max = self.linf_norm();
+        if max == 0. {
+            return 0.;
+        }
+        max * self
+            .0
+            .iter()
+            .map(|v| (v / max) * (v / max))
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    pub fn linf_norm(&self) -> f64 {
+        self.0.iter().fold(0., |acc, v| acc.max(v.abs()))
+    }
+
+    /// Whether every pair of corresponding coefficients differs by at most `tol`.
+    pub fn approx_eq(&self, other: &Self, tol: f64) -> bool {
+        let n = self.grade().max(other.grade());
+        self.pad_to_grade(n)
+            .into_iter()
+            .zip(other.pad_to_grade(n))
+            .all(|(a, b)| (a - b).abs() <= tol)
+    }
+
     pub fn lead(&self) -> f64 {
         self[self.grade()]
     }
 
+    pub fn lead_sign(&self) -> i32 {
+        match self.grade() {
+            -1 => 0,
+            _ => self.lead().signum() as i32,
+        }
+    }
+
+    /// The sign of `p(x)` as `x` goes to -∞ and +∞ respectively.
+    pub fn end_behavior(&self) -> (Ordering, Ordering) {
+        let sign = self.lead_sign().cmp(&0);
+        match self.grade() % 2 {
+            0 => (sign, sign),
+            _ => (sign.reverse(), sign),
+        }
+    }
+
+    pub fn is_monic(&self) -> bool {
+        self.grade() >= 0 && self.lead() == 1.
+    }
+
+    pub fn monic(&self) -> Self {
+        match self.grade() {
+            -1 => Self::ZERO,
+            _ => self.map_coefficients(|v| v / self.lead()),
+        }
+    }
+
+    /// The monic associate with exact rational coefficients, lowest first, or `None` if some
+    /// numerator or denominator doesn't fit in an `i32`. The division itself can't overflow, as
+    /// it is done over big rationals.
+    pub fn monic_rational(&self) -> Option<Vec<Rational32>> {
+        let v = self.to_ratios();
+        let Some(lead) = v.last() else {
+            return Some(vec![]);
+        };
+
+        v.iter()
+            .map(|c| {
+                let c = c / lead;
+                Some(Rational32::new_raw(
+                    c.numer().to_i32()?,
+                    c.denom().to_i32()?,
+                ))
+            })
+            .collect()
+    }
+
+    /// The exact value of each coefficient, lowest first. Fails with `NotRational32` at the first
+    /// coefficient whose numerator or denominator doesn't fit in an `i32`.
+    pub fn to_rationals(&self) -> Result<Vec<Rational32>, Error> {
+        self.to_ratios()
+            .iter()
+            .enumerate()
+            .map(|(index, c)| {
+                Ok(Rational32::new_raw(
+                    c.numer().to_i32().ok_or(Error::NotRational32 { index })?,
+                    c.denom().to_i32().ok_or(Error::NotRational32 { index })?,
+                ))
+            })
+            .collect()
+    }
+
+    /// Rounds each coefficient to the nearest `f64`.
+    pub fn from_rationals(coefficients: Vec<Rational32>) -> Self {
+        Self::normalize(
+            coefficients
+                .iter()
+                .map(|r| *r.numer() as f64 / *r.denom() as f64)
+                .collect(),
+        )
+    }
+
+    /// The rational roots with their multiplicities, found exactly by the rational root theorem,
+    /// and what is left after dividing them out. `None` if there are none, or if the coefficients
+    /// aren't small rationals and enumerating the candidate roots would be too expensive.
+    pub(crate) fn rational_roots(&self) -> Option<(Vec<(f64, i32)>, Polynomial)> {
+        const MAX_TERM: u64 = 1 << 20;
+
+        let n = self.grade();
+        if n <= 0 || self[0] == 0. || self.to_rationals().is_err() {
+            return None;
+        }
+
+        let mut r = self.to_ratios();
+        primitive(&mut r);
+        let term = |v: &BigRational| v.numer().abs().to_u64().filter(|&v| v <= MAX_TERM);
+        let numerators = divisors(term(&r[0])?);
+        let denominators = divisors(term(&r[n as usize])?);
+
+        let abs = self.map_coefficients(f64::abs);
+        let mut roots = vec![];
+
+        for (&a, &b) in numerators
+            .iter()
+            .flat_map(|a| denominators.iter().map(move |b| (a, b)))
+        {
+            let c = BigRational::from_u64(a)? / BigRational::from_u64(b)?;
+            // Each candidate appears again for every common factor of `a` and `b`.
+            if c.denom().to_u64() != Some(b) {
+                continue;
+            }
+
+            for c in [c.clone(), -c] {
+                // A cheap floating point check rules out most candidates before the exact one.
+                let x = ratio_to_f64(&c);
+                let scale = (n + 1) as f64 * abs.evaluate(x.abs());
+                if !self.eval_compensated(x).near_zero_rel(scale) {
+                    continue;
+                }
+
+                let mut multiplicity = 0;
+                while r.len() > 1 && eval_ratios(&r, &c).is_zero() {
+                    r = horner_div(r, &[-c.clone(), BigRational::one()]).0;
+                    multiplicity += 1;
+                }
+                if multiplicity > 0 {
+                    roots.push((x, multiplicity));
+                }
+            }
+        }
+
+        (!roots.is_empty()).then(|| (roots, Self::from_ratios(r)))
+    }
+
     pub fn primitive(&self) -> (Polynomial, f64) {
+        if self.grade() == -1 {
+            return (Self::ZERO, 0.);
+        }
+
         let mut r = self.to_ratios();
         let d = primitive(&mut r);
 
+        (Polynomial::from_ratios(r), ratio_to_f64(&d))
+    }
+
+    /// The largest number dividing all the coefficients into integers, signed like the leading
+    /// coefficient. Coefficients are taken at their exact binary value, so any finite input works.
+    pub fn content(&self) -> f64 {
+        self.primitive().1
+    }
+
+    /// The polynomial divided by its content: integer coefficients without a common factor and a
+    /// positive leading coefficient.
+    pub fn primitive_part(&self) -> Self {
+        self.primitive().0
+    }
+
+    /// Scales the polynomial by the least common multiple of its coefficients' denominators,
+    /// giving integer coefficients. Returns the scaled polynomial together with the factor.
+    /// As with `content`, coefficients are taken at their exact binary value.
+    pub fn map_to_monic_integer(&self) -> (Self, f64) {
+        if self.grade() == -1 {
+            return (Self::ZERO, 1.);
+        }
+
+        // The content of the coefficients is gcd(numerators) / lcm(denominators), in lowest terms.
+        let r = self.to_ratios();
+        let lcm = BigRational::from(primitive(&mut r.clone()).denom().clone());
+
         (
-            Polynomial::from_ratios(r),
-            *d.numer() as f64 / *d.denom() as f64,
+            Polynomial::from_ratios(r.into_iter().map(|v| v * &lcm).collect()),
+            ratio_to_f64(&lcm),
         )
     }
 
@@ -56,7 +385,7 @@ impl Polynomial {
 
     pub fn gsfd(&self) -> Self {
         match self.grade() {
-            -1 | 0 | 1 => self.clone(),
+            -1..=1 => self.clone(),
             _ => {
                 let s = self.to_ratios();
                 let g = gcd(s.clone(), self.derivative().to_ratios());
@@ -69,339 +398,2434 @@ impl Polynomial {
         }
     }
 
-    pub fn is_palindrome(&self) -> bool {
-        self.iter().all(|(i, v)| v == self[self.grade() - i])
+    /// Whether `p` has no repeated roots, real or complex, i.e. `gcd(p, p')` is constant.
+    pub fn is_squarefree(&self) -> bool {
+        self.is_squarefree_modulo_prime() || self.gcd(&self.derivative()).grade() <= 0
     }
 
-    pub fn root_bound(&self) -> Option<f64> {
-        let n = self.grade();
-        if n <= 0 {
-            return None;
+    /// A cheap sufficient condition for having no repeated roots: `p` has none modulo a prime
+    /// that doesn't divide its leading coefficient. Unlike the exact gcd, this stays fast when
+    /// the coefficients have huge denominators.
+    pub(crate) fn is_squarefree_modulo_prime(&self) -> bool {
+        if self.grade() <= 0 {
+            return true;
         }
 
-        let lead_abs = self.lead().abs();
-        let k = lead_abs.ilog2f() + 1;
+        let mut v = self.to_ratios();
+        primitive(&mut v);
+        let modulus = BigRational::from_u64(MODULUS).unwrap();
+        let f: Vec<_> = v
+            .iter()
+            .map(|c| (c % &modulus).to_i64().unwrap().rem_euclid(MODULUS as i64) as u64)
+            .collect();
+        if f.last() == Some(&0) {
+            return false;
+        }
 
-        (1..=n)
-            .filter(|i| self[n - i] != 0.)
-            .map(|i| {
-                let abs = self[n - i].abs();
-                let h = abs.ilog2f() + 1;
-                let mut e = (h - k - 1) / i + 1;
+        let df = f
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(|(i, c)| c * i as u64 % MODULUS)
+            .collect();
+        gcd_modular(f, df).len() == 1
+    }
 
-                if (k - h) % i == 2 % i && 2f64.powi(e * i) * lead_abs < abs {
-                    e += 1;
-                }
+    /// Yun's algorithm: returns pairwise coprime squarefree factors, each paired with its
+    /// multiplicity, whose product is the primitive part of the polynomial.
+    pub fn squarefree_factorization(&self) -> Vec<(Self, i32)> {
+        if self.grade() <= 0 {
+            return vec![];
+        }
 
-                e
-            })
-            .max()
-            .map(|e| 2f64.powi(e + 1))
-            .or(Some(f64::EPSILON))
-    }
+        let s = self.to_ratios();
+        let ds = self.derivative().to_ratios();
+        let b = gcd(s.clone(), ds.clone());
 
-    fn coef_ref(&self, i: i32) -> Option<&f64> {
-        self.0.get(i as usize).or_else(|| {
-            if i == 0 && self.grade() == -1 {
-                return Some(&0.);
+        let mut c = div(s, &b).0;
+        let mut d = sub(div(ds, &b).0, &derivative(&c));
+        let mut factors = vec![];
+
+        for i in 1.. {
+            if c.len() <= 1 {
+                break;
             }
-            None
-        })
-    }
 
-    fn evaluate(&self, v: f64) -> f64 {
-        match self.grade() {
-            -1 => 0.,
-            _ => self.0[0] + self.iter().skip(1).rev().fold(0., |a, (_, c)| v * (a + c)),
+            let a = gcd(c.clone(), d.clone());
+            c = div(c, &a).0;
+            d = sub(div(d, &a).0, &derivative(&c));
+
+            if a.len() > 1 {
+                factors.push((Self::from_ratios(a), i));
+            }
         }
+
+        factors
     }
 
-    fn to_ratios(&self) -> Vec<Rational32> {
-        self.0
-            .iter()
-            .map(|&v| Rational32::from_f64(v))
-            .collect::<Option<_>>()
-            .expect("values too big")
+    /// Every root, real and complex, as the eigenvalues of the companion matrix. Unlike the
+    /// closed forms used by `find_roots`, the result is only accurate to about `ε·‖p‖` relative to
+    /// the coefficients, and roots of multiplicity `m` spread out by roughly `ε^(1/m)`; nearby
+    /// eigenvalues are merged into a single root. `None` for the zero polynomial, or if the
+    /// QR iteration fails to converge.
+    pub fn all_roots_eig(&self) -> Option<Vec<ComplexRoot>> {
+        companion_roots(self)
     }
 
-    fn from_ratios(r: Vec<Rational32>) -> Self {
-        Self(
-            r.into_iter()
-                .map(|v| *v.numer() as f64 / *v.denom() as f64)
-                .collect(),
-        )
+    /// The companion matrix after Parlett–Reinsch balancing, as the rows of an `n×n` matrix
+    /// whose eigenvalues are the roots. `None` for the zero polynomial.
+    pub fn companion_balanced(&self) -> Option<Vec<Vec<f64>>> {
+        balanced_companion(self)
     }
-}
 
-impl Default for Polynomial {
-    fn default() -> Self {
-        Self::ZERO
+    /// All the roots, real and complex, found with the Jenkins–Traub algorithm and sorted by
+    /// their real parts.
+    pub fn jenkins_traub(&self) -> Vec<ComplexRoot> {
+        jenkins_traub_roots(self)
     }
-}
 
-impl<T> From<T> for Polynomial
-where
-    T: Into<Vec<f64>>,
-{
-    fn from(value: T) -> Self {
-        let v: Vec<_> = value.into();
+    /// The real roots, found by solving the reciprocal and inverting its roots. `None` for the zero
+    /// polynomial, if 0 is a root, or if the scaled reciprocal overflows.
+    pub fn reverse_and_solve(&self) -> Option<Vec<Root>> {
+        reciprocal_roots(self)
+    }
 
-        if v.len() > i32::MAX as usize {
-            panic!("Too many coefficients");
-        }
+    /// Finds the real roots one at a time, deflating each out before looking for the next.
+    pub fn solve_by_deflation(&self) -> Vec<Root> {
+        deflation_roots(self)
+    }
 
-        if v.iter().any(|v| !v.is_finite()) {
-            panic!("Coefficients are not finite floats");
-        }
+    pub fn pow(&self, mut n: u32) -> Self {
+        let mut base = self.clone();
+        let mut res = Self(vec![1.]);
+
+        while n > 0 {
+            if n % 2 == 1 {
+                res = &res * &base;
+            }
 
-        if v.len() == 1 && v[0] == 0. {
-            return Self::ZERO;
+            n /= 2;
+            if n > 0 {
+                base = &base * &base;
+            }
         }
 
-        Self(v)
+        res
     }
-}
 
-impl Index<i32> for Polynomial {
-    type Output = f64;
-
-    fn index(&self, i: i32) -> &Self::Output {
-        self.coef_ref(i).unwrap()
+    pub fn scale_input(&self, c: f64) -> Self {
+        let n = self.grade();
+        self.iter()
+            .map(|(i, v)| v * c.powi(n - i))
+            .collect::<Vec<_>>()
+            .into()
     }
-}
 
-impl FnOnce<(f64,)> for Polynomial {
-    type Output = f64;
+    /// The polynomial `q` without an `x^(n-1)` term and the shift `s` such that `p(x) = q(x + s)`,
+    /// so the roots of `p` are those of `q` minus `s`.
+    pub fn depress(&self) -> (Self, f64) {
+        let n = self.grade();
+        if n <= 0 {
+            return (self.clone(), 0.);
+        }
 
-    extern "rust-call" fn call_once(self, args: (f64,)) -> Self::Output {
-        self.evaluate(args.0)
-    }
-}
+        let s = self[n - 1] / (n as f64 * self.lead());
+        let mut q = self.shift(s).0;
+        // Cancellation leaves a rounding error behind instead of an exact zero.
+        q[n as usize - 1] = 0.;
 
-impl FnMut<(f64,)> for Polynomial {
-    extern "rust-call" fn call_mut(&mut self, args: (f64,)) -> Self::Output {
-        self.evaluate(args.0)
+        (Self::normalize(q), s)
     }
-}
 
-impl Fn<(f64,)> for Polynomial {
-    extern "rust-call" fn call(&self, args: (f64,)) -> Self::Output {
-        self.evaluate(args.0)
+    pub fn reflect_y(&self) -> Self {
+        Self(
+            self.iter()
+                .map(|(i, v)| if i % 2 == 1 { v.negate() } else { v })
+                .collect(),
+        )
     }
-}
 
-impl fmt::Display for Polynomial {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if self.grade() == -1 {
-            return f.write_char('0');
+    pub fn shift(&self, h: f64) -> Self {
+        let mut res = self.0.clone();
+        let n = res.len();
+
+        for i in 0..n {
+            for j in (i..n - 1).rev() {
+                res[j] -= h * res[j + 1];
+            }
         }
 
-        let s = self
+        res.into()
+    }
+
+    /// The running sums of synthetic division by `x - r`, from the leading coefficient down: all
+    /// but the last are the quotient's coefficients, the last is the remainder `p(r)`.
+    pub fn horner_table(&self, r: f64) -> Vec<f64> {
+        self.0
             .iter()
-            .flat_map(|(i, v)| format_coefficient(v, i, "x", i == self.grade()))
             .rev()
-            .collect::<String>();
+            .scan(0., |acc, &c| {
+                *acc = *acc * r + c;
+                Some(*acc)
+            })
+            .collect()
+    }
 
-        f.write_str(&s)
+    /// Divides by `x - root` in floating point, dropping the remainder. Rounding errors in a
+    /// deflated polynomial carry over to every later deflation, so when removing several roots
+    /// deflate the smallest in magnitude first.
+    pub fn synthetic_deflate(&self, root: f64) -> Self {
+        let mut table = self.horner_table(root);
+        table.pop();
+        table.reverse();
+        table.into()
     }
-}
 
-fn format_coefficient(v: f64, pow: i32, var: &str, first: bool) -> Option<String> {
-    if v == 0.0 {
-        return None;
+    pub fn resultant(&self, other: &Self) -> f64 {
+        ratio_to_f64(&resultant(self.to_ratios(), other.to_ratios()))
     }
 
-    let mut ret = String::new();
+    /// Whether the two have a common complex root, which is when their resultant is 0. Unlike
+    /// `resultant`, the check is exact even when the resultant would round to 0 as an `f64`.
+    pub fn shares_root_with(&self, other: &Self) -> bool {
+        match (self.grade(), other.grade()) {
+            (0, _) | (_, 0) => false,
+            (-1, _) | (_, -1) => true,
+            _ => resultant(self.to_ratios(), other.to_ratios()).is_zero(),
+        }
+    }
 
-    if !first && v >= 0. {
-        ret += "+";
+    /// The discriminant, zero exactly when there is a repeated root. By convention it is 1 for
+    /// linear polynomials, and 0 for constants and the zero polynomial.
+    pub fn discriminant(&self) -> f64 {
+        let n = self.grade();
+        if n < 1 {
+            return 0.;
+        }
+        if n == 1 {
+            return 1.;
+        }
+
+        let r = self.to_ratios();
+        let mut d = resultant(r.clone(), self.derivative().to_ratios()) / &r[n as usize];
+        if (n * (n - 1) / 2) % 2 == 1 {
+            d = -d;
+        }
+
+        ratio_to_f64(&d)
     }
 
-    if v != 1.0 || pow == 0 {
-        ret += v.to_string().as_ref();
+    pub fn map_coefficients(&self, f: impl Fn(f64) -> f64) -> Self {
+        self.0.iter().map(|&v| f(v)).collect::<Vec<_>>().into()
     }
 
-    if pow > 0 {
-        ret += var;
+    pub fn round_coefficients(&self, tol: f64) -> Self {
+        self.map_coefficients(|v| {
+            if (v - v.round()).near_zero_within(tol) {
+                v.round()
+            } else {
+                v
+            }
+        })
     }
 
-    if pow > 1 {
-        ret += "^";
-        ret += pow.to_string().as_ref();
+    pub fn truncate_to_grade(&self, n: i32) -> Self {
+        let len = n.saturating_add(1).clamp(0, self.0.len() as i32) as usize;
+        self.0[..len].to_vec().into()
     }
 
-    Some(ret)
-}
+    /// The coefficients, lowest first, zero-extended to `n + 1` of them. Polynomials never hold
+    /// zero leading coefficients, so this returns a plain vector, e.g. for elementwise operations.
+    pub fn pad_to_grade(&self, n: i32) -> Vec<f64> {
+        let mut res = self.0.clone();
+        if n >= self.grade() {
+            res.resize((n + 1) as usize, 0.);
+        }
+        res
+    }
 
-const ZERO: Rational32 = Rational32::new_raw(0, 1);
-const ONE: Rational32 = Rational32::new_raw(1, 1);
+    /// Returns the polynomial whose roots are the reciprocals of this one's roots.
+    /// A root at 0 has no finite reciprocal, so it is dropped and the grade decreases.
+    pub fn reciprocal(&self) -> Self {
+        Self::normalize(self.0.iter().rev().copied().collect())
+    }
 
-fn horner_div(mut lhs: Vec<Rational32>, rhs: &[Rational32]) -> (Vec<Rational32>, Rational32) {
-    let a = -rhs[0] / rhs[1];
+    pub fn is_even(&self) -> bool {
+        self.iter().all(|(i, v)| i % 2 == 0 || v == 0.)
+    }
 
-    (0..lhs.len() - 1).rev().for_each(|k| {
-        let prev = lhs[k + 1];
-        lhs[k] += a * prev;
-    });
+    pub fn is_odd(&self) -> bool {
+        self.iter().all(|(i, v)| i % 2 == 1 || v == 0.)
+    }
 
-    lhs.rotate_left(1);
-    let rem = lhs.pop().unwrap();
+    pub fn is_palindrome(&self) -> bool {
+        self.iter().all(|(i, v)| v == self[self.grade() - i])
+    }
+
+    pub fn root_bound(&self) -> Option<f64> {
+        let n = self.grade();
+        if n <= 0 {
+            return None;
+        }
+
+        let lead_abs = self.lead().abs();
+        let k = lead_abs.ilog2f() + 1;
+
+        (1..=n)
+            .filter(|i| self[n - i] != 0.)
+            .map(|i| {
+                let abs = self[n - i].abs();
+                let h = abs.ilog2f() + 1;
+                let mut e = (h - k - 1) / i + 1;
+
+                if (k - h) % i == 2 % i && 2f64.powi(e * i) * lead_abs < abs {
+                    e += 1;
+                }
+
+                e
+            })
+            .max()
+            .map(|e| 2f64.powi(e + 1))
+            .or(Some(f64::EPSILON))
+    }
+
+    pub fn sign_variations(&self) -> i32 {
+        sign_changes(self.0.iter().copied())
+    }
+
+    pub fn descartes_bounds(&self) -> (i32, i32) {
+        (self.sign_variations(), self.reflect_y().sign_variations())
+    }
+
+    /// The number of distinct real roots in `(a, b]`, counted exactly with a Sturm sequence.
+    /// Either bound may be infinite.
+    pub fn count_roots_in(&self, a: f64, b: f64) -> Result<i32, Error> {
+        check_bounds(a, b)?;
+        if self.grade() <= 0 {
+            return Ok(0);
+        }
+
+        let seq = sturm_sequence(self.to_ratios());
+        Ok(sturm_variations(&seq, a) - sturm_variations(&seq, b))
+    }
+
+    /// The exact number of real roots, counted once each unless `with_multiplicity` is set.
+    /// The zero polynomial is reported as having none.
+    pub fn real_root_count(&self, with_multiplicity: bool) -> i32 {
+        if self.grade() <= 0 {
+            return 0;
+        }
+        let count = |p: &Self| {
+            p.count_roots_in(f64::NEG_INFINITY, f64::INFINITY)
+                .expect("bounds are not NaN")
+        };
+
+        if with_multiplicity {
+            self.squarefree_factorization()
+                .iter()
+                .map(|(factor, multiplicity)| multiplicity * count(factor))
+                .sum()
+        } else {
+            count(self)
+        }
+    }
+
+    /// Disjoint intervals `(a, b]`, in increasing order, each containing exactly one distinct real
+    /// root, found by bisecting with a Sturm sequence.
+    pub fn isolate_roots(&self) -> Vec<(f64, f64)> {
+        let Some(bound) = self.root_bound() else {
+            return vec![];
+        };
+
+        // At a multiple root the whole sequence vanishes, so it is built from the squarefree part.
+        let s = self.to_ratios();
+        let g = gcd(s.clone(), derivative(&s));
+        let seq = sturm_sequence(div(s, &g).0);
+        let (lo, hi) = (-2. * bound, 2. * bound);
+        let mut pending = vec![(
+            lo,
+            hi,
+            sturm_variations(&seq, lo),
+            sturm_variations(&seq, hi),
+        )];
+        let mut intervals = vec![];
+
+        while let Some((a, b, va, vb)) = pending.pop() {
+            let mid = (a + b) / 2.;
+            match va - vb {
+                0 => {}
+                // Roots closer together than f64 can tell apart stay in one interval.
+                n if n == 1 || mid <= a || mid >= b => intervals.push((a, b)),
+                _ => {
+                    let vm = sturm_variations(&seq, mid);
+                    pending.push((mid, b, vm, vb));
+                    pending.push((a, mid, va, vm));
+                }
+            }
+        }
+
+        intervals
+    }
+
+    /// The sign of `p` at the ends of each interval from `isolate_roots`, with the root it brackets.
+    /// The signs differ across roots of odd multiplicity and agree across those of even multiplicity.
+    pub fn sign_table(&self, intervals: &[(f64, f64)]) -> Vec<SignInterval> {
+        if self.grade() <= 0 {
+            return vec![];
+        }
+
+        let s = self.to_ratios();
+        let g = gcd(s.clone(), derivative(&s));
+        let squarefree = Self::from_ratios(div(s, &g).0);
+        let sign = |x: f64| {
+            self.eval_compensated(x)
+                .partial_cmp(&0.)
+                .unwrap_or(Ordering::Equal)
+        };
+
+        intervals
+            .iter()
+            .map(|&(a, b)| SignInterval {
+                a,
+                b,
+                sign_a: sign(a),
+                sign_b: sign(b),
+                root: bisect_root(&squarefree, a, b, 0.),
+            })
+            .collect()
+    }
+
+    /// An upper bound on the number of real roots in `(a, b]`, counted with multiplicity, by the
+    /// Budan–Fourier theorem. It exceeds the actual count by an even number, so it is exact only
+    /// when it is 0 or 1, but it is much cheaper than `count_roots_in`.
+    pub fn budan_fourier_count(&self, a: f64, b: f64) -> i32 {
+        let variations = |x: f64| {
+            sign_changes(
+                (0..=self.grade().max(0) as u32).map(|k| self.derivative_n(k).eval_compensated(x)),
+            )
+        };
+
+        variations(a) - variations(b)
+    }
+
+    /// The value at `re + im·i`, as its real and imaginary parts.
+    pub fn eval_complex(&self, re: f64, im: f64) -> (f64, f64) {
+        let z = Complex64::new(re, im);
+        let v = self
+            .0
+            .iter()
+            .rev()
+            .fold(Complex64::ZERO, |acc, &c| acc * z + c);
+        (v.re, v.im)
+    }
+
+    pub fn eval_compensated(&self, x: f64) -> f64 {
+        let Some((&lead, rest)) = self.0.split_last() else {
+            return 0.;
+        };
+
+        let (res, err) = rest.iter().rev().fold((lead, 0.), |(s, c), &a| {
+            let (p, p_err) = two_prod(s, x);
+            let (s, s_err) = two_sum(p, a);
+            (s, c * x + (p_err + s_err))
+        });
+
+        res + err
+    }
+
+    /// `p(x)` and `p'(x)` in a single Horner sweep, the value being compensated as in
+    /// `eval_compensated`.
+    pub fn eval_with_derivative(&self, x: f64) -> (f64, f64) {
+        let Some((&lead, rest)) = self.0.split_last() else {
+            return (0., 0.);
+        };
+
+        let (res, err, d) = rest.iter().rev().fold((lead, 0., 0.), |(s, c, d), &a| {
+            let d = d * x + s;
+            let (p, p_err) = two_prod(s, x);
+            let (s, s_err) = two_sum(p, a);
+            (s, c * x + (p_err + s_err), d)
+        });
+
+        (res + err, d)
+    }
+
+    /// `p(x)`, `p'(x)`, `p''(x)` and so on into `out`, as many as fit, in a single Horner sweep and
+    /// without allocating. The value is compensated as in `eval_compensated`.
+    #[inline]
+    pub fn eval_derivatives(&self, x: f64, out: &mut [f64]) {
+        out.fill(0.);
+        if out.is_empty() {
+            return;
+        }
+
+        let mut err = 0.;
+        for &a in self.0.iter().rev() {
+            for k in (1..out.len()).rev() {
+                out[k] = out[k] * x + out[k - 1];
+            }
+            let (p, p_err) = two_prod(out[0], x);
+            let (s, s_err) = two_sum(p, a);
+            out[0] = s;
+            err = err * x + (p_err + s_err);
+        }
+        out[0] += err;
+
+        // The sweep gives the Taylor coefficients p^(k)(x) / k!.
+        let mut factorial = 1.;
+        for (k, v) in out.iter_mut().enumerate().skip(2) {
+            factorial *= k as f64;
+            *v *= factorial;
+        }
+    }
+
+    pub fn is_root(&self, x: f64) -> bool {
+        self.is_root_within(x, TOLERANCE)
+    }
+
+    /// Whether `p(x)` is at most `tol` relative to the size of the terms of `p(x)`.
+    pub fn is_root_within(&self, x: f64, tol: f64) -> bool {
+        // The rounding error of p(x) grows with the size of the terms, not of the result.
+        let scale = self.map_coefficients(f64::abs).evaluate(x.abs());
+        self.eval_compensated(x).near_zero_rel_within(scale, tol)
+    }
+
+    pub fn root_multiplicity_at(&self, x: f64) -> i32 {
+        self.root_multiplicity_at_within(x, TOLERANCE)
+    }
+
+    pub fn root_multiplicity_at_within(&self, x: f64, tol: f64) -> i32 {
+        (0..=self.grade())
+            .take_while(|&k| self.derivative_n(k as u32).is_root_within(x, tol))
+            .count() as i32
+    }
+
+    pub fn eval_interval(&self, lo: f64, hi: f64) -> (f64, f64) {
+        debug_assert!(lo <= hi);
+
+        // Every operation is rounded outwards, so the result encloses all of p([lo, hi]).
+        self.0.iter().rev().fold((0., 0.), |(a, b), &c| {
+            let products = [a * lo, a * hi, b * lo, b * hi];
+            let min = products
+                .into_iter()
+                .fold(f64::INFINITY, f64::min)
+                .next_down();
+            let max = products
+                .into_iter()
+                .fold(f64::NEG_INFINITY, f64::max)
+                .next_up();
+
+            ((min + c).next_down(), (max + c).next_up())
+        })
+    }
+
+    /// `(x, p(x))` at `n` evenly spaced points of `[a, b]`, both ends included. A single sample is
+    /// taken at `a`.
+    pub fn sample(&self, a: f64, b: f64, n: usize) -> Vec<(f64, f64)> {
+        let step = (b - a) / (n.max(2) - 1) as f64;
+        (0..n)
+            .map(|k| {
+                if k + 1 == n && n > 1 {
+                    b
+                } else {
+                    a + step * k as f64
+                }
+            })
+            .map(|x| (x, self(x)))
+            .collect()
+    }
+
+    /// Heuristic: counts the sign changes of `p` between evenly spaced samples of `[a, b]`.
+    /// Roots closer together than the sample spacing, and roots of even multiplicity, are missed.
+    /// Infinite bounds are sampled from twice the root bound, past which the sign stays the same.
+    pub fn count_sign_changes_in(&self, a: f64, b: f64) -> Result<i32, Error> {
+        check_bounds(a, b)?;
+        let bound = (2. * self.root_bound().unwrap_or(1.)).min(f64::MAX / 2.);
+        let clamp = |x: f64| {
+            if x.is_infinite() {
+                bound.copysign(x)
+            } else {
+                x
+            }
+        };
+
+        Ok(self
+            .sample(clamp(a), clamp(b), SIGN_CHANGE_SAMPLES + 1)
+            .into_iter()
+            .map(|(_, v)| v)
+            .filter(|&v| v != 0.)
+            .map_windows(|[x, y]| (x.is_sign_negative() != y.is_sign_negative()) as i32)
+            .sum())
+    }
+
+    /// Estimates the magnitudes of all the roots, in ascending order, by repeatedly squaring
+    /// them with Graeffe's method and reading the moduli off the Newton polygon of the result.
+    pub fn graeffe_magnitudes(&self, iterations: u32) -> Vec<f64> {
+        let n = self.grade();
+        if n <= 0 {
+            return vec![];
+        }
+
+        // Each coefficient is kept as its sign and the logarithm of its magnitude, since squaring
+        // the roots quickly takes the coefficients out of the range of f64.
+        let mut c: Vec<_> = self.0.iter().map(|&v| (v.signum(), v.abs().ln())).collect();
+        for _ in 0..iterations {
+            c = (0..=n)
+                .map(|i| {
+                    let terms: Vec<_> = (0..=2 * i)
+                        .filter(|&j| j <= n && 2 * i - j <= n)
+                        .map(|j| {
+                            let ((sa, la), (sb, lb)) = (c[j as usize], c[(2 * i - j) as usize]);
+                            let sign = if (j + n) % 2 == 0 { 1. } else { -1. };
+                            (sign * sa * sb, la + lb)
+                        })
+                        .collect();
+
+                    let max = terms.iter().fold(f64::NEG_INFINITY, |m, t| m.max(t.1));
+                    if max == f64::NEG_INFINITY {
+                        return (1., max);
+                    }
+                    let s: f64 = terms.iter().map(|(s, l)| s * (l - max).exp()).sum();
+                    (s.signum(), max + s.abs().ln())
+                })
+                .collect();
+        }
+
+        let points: Vec<_> = c
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, l))| *l != f64::NEG_INFINITY)
+            .map(|(i, &(_, l))| (i as f64, l))
+            .collect();
+
+        let mut hull: Vec<(f64, f64)> = vec![];
+        for p in points {
+            while let [.., a, b] = hull[..] {
+                if (b.0 - a.0) * (p.1 - a.1) - (b.1 - a.1) * (p.0 - a.0) < 0. {
+                    break;
+                }
+                hull.pop();
+            }
+            hull.push(p);
+        }
+
+        let zeros = hull[0].0 as usize;
+        let scale = 2f64.powi(iterations as i32);
+
+        core::iter::repeat_n(0., zeros)
+            .chain(hull.windows(2).flat_map(|w| {
+                let len = w[1].0 - w[0].0;
+                let modulus = ((w[0].1 - w[1].1) / (len * scale)).exp();
+                core::iter::repeat_n(modulus, len as usize)
+            }))
+            .collect()
+    }
+
+    fn coef_ref(&self, i: i32) -> Option<&f64> {
+        self.0.get(i as usize).or_else(|| {
+            if i == 0 && self.grade() == -1 {
+                return Some(&0.);
+            }
+            None
+        })
+    }
+
+    pub fn to_string_with_var(&self, var: &str) -> String {
+        if self.grade() == -1 {
+            return "0".into();
+        }
+
+        self.terms_desc()
+            .map(|(i, v)| format_coefficient(v, i, var, i == self.grade()))
+            .collect()
+    }
+
+    fn evaluate(&self, v: f64) -> f64 {
+        self.0.iter().rev().fold(0., |a, &c| a * v + c)
+    }
+
+    fn to_ratios(&self) -> Vec<BigRational> {
+        self.0
+            .iter()
+            .map(|&v| BigRational::from_f64(v))
+            .collect::<Option<_>>()
+            .expect("coefficients are finite")
+    }
+
+    fn from_ratios(r: Vec<BigRational>) -> Self {
+        // Tiny rationals can round to 0 as f64, leaving zeros at the end.
+        Self::normalize(r.iter().map(ratio_to_f64).collect())
+    }
+}
+
+impl FromStr for Polynomial {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        parse_coefs(tokenize(s), false).map(Self::from)
+    }
+}
+
+impl Default for Polynomial {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+/// A row of `Polynomial::sign_table`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SignInterval {
+    pub a: f64,
+    pub b: f64,
+    pub sign_a: Ordering,
+    pub sign_b: Ordering,
+    pub root: f64,
+}
+
+/// Builds a polynomial term by term, for sparse polynomials such as `x^100 + 1`. Setting the
+/// same degree twice keeps the last coefficient.
+#[derive(Debug, Clone, Default)]
+pub struct PolynomialBuilder(Vec<(u32, f64)>);
+
+impl PolynomialBuilder {
+    pub fn term(mut self, degree: u32, coefficient: f64) -> Self {
+        self.0.push((degree, coefficient));
+        self
+    }
+
+    /// Errors refer to the terms in the order they were added.
+    pub fn build(self) -> Result<Polynomial, Error> {
+        if let Some(index) = self.0.iter().position(|(_, c)| !c.is_finite()) {
+            return Err(Error::NonFinite { index });
+        }
+
+        let len = self
+            .0
+            .iter()
+            .map(|&(d, _)| d as usize + 1)
+            .max()
+            .unwrap_or(0);
+        if len > i32::MAX as usize {
+            return Err(Error::TooManyCoefficients { count: len });
+        }
+
+        let mut coefs = vec![0.; len];
+        for (degree, c) in self.0 {
+            coefs[degree as usize] = c;
+        }
+
+        Polynomial::try_from_coefficients(coefs)
+    }
+}
+
+impl<T> From<T> for Polynomial
+where
+    T: Into<Vec<f64>>,
+{
+    fn from(value: T) -> Self {
+        Self::try_from_coefficients(value).unwrap_or_else(|err| panic!("{err}"))
+    }
+}
+
+impl Index<i32> for Polynomial {
+    type Output = f64;
+
+    fn index(&self, i: i32) -> &Self::Output {
+        self.coef_ref(i).unwrap()
+    }
+}
+
+impl Add for &Polynomial {
+    type Output = Polynomial;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let (long, short) = if self.0.len() >= rhs.0.len() {
+            (self, rhs)
+        } else {
+            (rhs, self)
+        };
+
+        let mut res = long.0.clone();
+        res.iter_mut().zip(&short.0).for_each(|(a, b)| *a += b);
+
+        Polynomial::normalize(res)
+    }
+}
+
+impl Add for Polynomial {
+    type Output = Polynomial;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        &self + &rhs
+    }
+}
+
+impl Sub for &Polynomial {
+    type Output = Polynomial;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + &-rhs
+    }
+}
+
+impl Sub for Polynomial {
+    type Output = Polynomial;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        &self - &rhs
+    }
+}
+
+impl Neg for &Polynomial {
+    type Output = Polynomial;
+
+    fn neg(self) -> Self::Output {
+        Polynomial(self.0.iter().map(|v| v.negate()).collect())
+    }
+}
+
+impl Neg for Polynomial {
+    type Output = Polynomial;
+
+    fn neg(self) -> Self::Output {
+        -&self
+    }
+}
+
+impl Mul for &Polynomial {
+    type Output = Polynomial;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        if self.grade() == -1 || rhs.grade() == -1 {
+            return Polynomial::ZERO;
+        }
+
+        let mut res = vec![0.; self.0.len() + rhs.0.len() - 1];
+        for (i, a) in self.iter() {
+            for (j, b) in rhs.iter() {
+                res[(i + j) as usize] += a * b;
+            }
+        }
+
+        Polynomial::normalize(res)
+    }
+}
+
+impl Mul for Polynomial {
+    type Output = Polynomial;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        &self * &rhs
+    }
+}
+
+impl Div for &Polynomial {
+    type Output = Polynomial;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        self.quotient(rhs)
+    }
+}
+
+impl Div for Polynomial {
+    type Output = Polynomial;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        &self / &rhs
+    }
+}
+
+impl Rem for &Polynomial {
+    type Output = Polynomial;
+
+    fn rem(self, rhs: Self) -> Self::Output {
+        self.remainder(rhs)
+    }
+}
+
+impl Rem for Polynomial {
+    type Output = Polynomial;
+
+    fn rem(self, rhs: Self) -> Self::Output {
+        &self % &rhs
+    }
+}
+
+impl Sum for Polynomial {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Polynomial::ZERO, |acc, p| acc + p)
+    }
+}
+
+impl<'a> Sum<&'a Polynomial> for Polynomial {
+    fn sum<I: Iterator<Item = &'a Polynomial>>(iter: I) -> Self {
+        iter.fold(Polynomial::ZERO, |acc, p| &acc + p)
+    }
+}
+
+impl Product for Polynomial {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Polynomial(vec![1.]), |acc, p| acc * p)
+    }
+}
+
+impl<'a> Product<&'a Polynomial> for Polynomial {
+    fn product<I: Iterator<Item = &'a Polynomial>>(iter: I) -> Self {
+        iter.fold(Polynomial(vec![1.]), |acc, p| &acc * p)
+    }
+}
+
+impl IntoIterator for Polynomial {
+    type Item = (i32, f64);
+    type IntoIter = Map<Enumerate<vec::IntoIter<f64>>, fn((usize, f64)) -> (i32, f64)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter().enumerate().map(|(i, v)| (i as i32, v))
+    }
+}
+
+impl<'a> IntoIterator for &'a Polynomial {
+    type Item = (i32, f64);
+    type IntoIter = Map<Enumerate<slice::Iter<'a, f64>>, fn((usize, &f64)) -> (i32, f64)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter().enumerate().map(|(i, &v)| (i as i32, v))
+    }
+}
+
+impl FnOnce<(f64,)> for Polynomial {
+    type Output = f64;
+
+    extern "rust-call" fn call_once(self, args: (f64,)) -> Self::Output {
+        self.evaluate(args.0)
+    }
+}
+
+impl FnMut<(f64,)> for Polynomial {
+    extern "rust-call" fn call_mut(&mut self, args: (f64,)) -> Self::Output {
+        self.evaluate(args.0)
+    }
+}
+
+impl Fn<(f64,)> for Polynomial {
+    extern "rust-call" fn call(&self, args: (f64,)) -> Self::Output {
+        self.evaluate(args.0)
+    }
+}
+
+impl fmt::Display for Polynomial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_string_with_var("x"))
+    }
+}
+
+fn format_coefficient(v: f64, pow: i32, var: &str, first: bool) -> String {
+    let mut ret = String::new();
+
+    if !first && v >= 0. {
+        ret += "+";
+    }
+
+    if v != 1.0 || pow == 0 {
+        ret += v.to_string().as_ref();
+    }
+
+    if pow > 0 {
+        ret += var;
+    }
+
+    if pow > 1 {
+        ret += "^";
+        ret += pow.to_string().as_ref();
+    }
+
+    ret
+}
+
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let s = a + b;
+    let z = s - a;
+    (s, (a - (s - z)) + (b - z))
+}
+
+fn two_prod(a: f64, b: f64) -> (f64, f64) {
+    let p = a * b;
+    (p, a.mul_add(b, -p))
+}
+
+fn ratio_to_f64(v: &BigRational) -> f64 {
+    v.to_f64().expect("value fits in a float")
+}
+
+fn horner_div(mut lhs: Vec<BigRational>, rhs: &[BigRational]) -> (Vec<BigRational>, BigRational) {
+    let a = -&rhs[0] / &rhs[1];
+
+    (0..lhs.len() - 1).rev().for_each(|k| {
+        let prev = &a * &lhs[k + 1];
+        lhs[k] += prev;
+    });
+
+    lhs.rotate_left(1);
+    let rem = lhs.pop().unwrap();
+
+    if !rhs[1].is_one() {
+        lhs.iter_mut().for_each(|v| *v /= &rhs[1]);
+    }
+
+    (lhs, rem)
+}
+
+fn long_div(
+    mut lhs: Vec<BigRational>,
+    rhs: &[BigRational],
+) -> (Vec<BigRational>, Vec<BigRational>) {
+    let init_l_grade = lhs.len() - 1;
+    let init_r_grade = rhs.len() - 1;
+    if init_l_grade < init_r_grade {
+        return (vec![], lhs);
+    }
+
+    let res_g = init_l_grade - init_r_grade;
+    let mut res = vec![BigRational::zero(); res_g + 1];
+
+    while lhs.len() >= rhs.len() {
+        let l_g = lhs.len() - 1;
+        let r_g = rhs.len() - 1;
+        let c = &lhs[l_g] / &rhs[r_g];
+
+        (0..=r_g).for_each(|k| lhs[l_g - k] -= &c * &rhs[r_g - k]);
+
+        while let Some(v) = lhs.last() {
+            if !v.is_zero() {
+                break;
+            }
+
+            lhs.pop();
+        }
+
+        res[res_g - (init_l_grade - l_g)] = c;
+    }
+
+    (res, lhs)
+}
+
+fn div(mut lhs: Vec<BigRational>, rhs: &[BigRational]) -> (Vec<BigRational>, Vec<BigRational>) {
+    match rhs.len() {
+        0 => panic!("Division by 0"),
+        _ if lhs.is_empty() => (vec![], vec![]),
+        1 => {
+            lhs.iter_mut().for_each(|v| *v /= &rhs[0]);
+            (lhs, vec![])
+        }
+        2 => {
+            let (res, rem) = horner_div(lhs, rhs);
+            (res, if rem.is_zero() { vec![] } else { vec![rem] })
+        }
+        _ => long_div(lhs, rhs),
+    }
+}
+
+fn gcd(mut r0: Vec<BigRational>, mut r1: Vec<BigRational>) -> Vec<BigRational> {
+    if r0.len() < r1.len() {
+        core::mem::swap(&mut r0, &mut r1);
+    }
+
+    while !r1.is_empty() {
+        let (_, rem) = div(r0, &r1);
+        r0 = r1;
+        r1 = rem;
+    }
+
+    primitive(&mut r0);
+
+    r0
+}
+
+fn derivative(v: &[BigRational]) -> Vec<BigRational> {
+    v.iter()
+        .enumerate()
+        .skip(1)
+        .map(|(i, c)| c * BigRational::from_integer(i.into()))
+        .collect()
+}
+
+fn sub(mut lhs: Vec<BigRational>, rhs: &[BigRational]) -> Vec<BigRational> {
+    if lhs.len() < rhs.len() {
+        lhs.resize(rhs.len(), BigRational::zero());
+    }
+
+    lhs.iter_mut().zip(rhs).for_each(|(a, b)| *a -= b);
+
+    while lhs.last().is_some_and(|v| v.is_zero()) {
+        lhs.pop();
+    }
+
+    lhs
+}
+
+fn resultant(mut a: Vec<BigRational>, mut b: Vec<BigRational>) -> BigRational {
+    let mut res = BigRational::one();
+
+    loop {
+        if a.is_empty() || b.is_empty() {
+            return BigRational::zero();
+        }
+
+        let (m, n) = (a.len() - 1, b.len() - 1);
+        if n == 0 {
+            return res * num_traits::pow(b.pop().unwrap(), m);
+        }
+
+        if m % 2 == 1 && n % 2 == 1 {
+            res = -res;
+        }
+
+        let (_, r) = div(a, &b);
+        if r.is_empty() {
+            return BigRational::zero();
+        }
+
+        res *= num_traits::pow(b[n].clone(), m - (r.len() - 1));
+        a = b;
+        b = r;
+    }
+}
+
+fn sturm_sequence(v: Vec<BigRational>) -> Vec<Vec<BigRational>> {
+    let d = derivative(&v);
+    let mut seq = vec![v, d];
+
+    loop {
+        let [.., prev, last] = &seq[..] else {
+            unreachable!()
+        };
+        let rem = div(prev.clone(), last).1;
+        if rem.is_empty() {
+            return seq;
+        }
+
+        seq.push(rem.into_iter().map(|v| -v).collect());
+    }
+}
+
+fn check_bounds(a: f64, b: f64) -> Result<(), Error> {
+    match a.is_nan() || b.is_nan() {
+        true => Err(Error::NanBound),
+        false => Ok(()),
+    }
+}
+
+fn sturm_variations(seq: &[Vec<BigRational>], x: f64) -> i32 {
+    debug_assert!(!x.is_nan());
+
+    match BigRational::from_f64(x) {
+        Some(x) => sign_changes(seq.iter().map(|v| eval_ratios(v, &x).signum())),
+        // Past every root each polynomial has the sign of its leading term, flipped at -∞ for
+        // odd degrees.
+        None => sign_changes(seq.iter().map(|v| {
+            let lead = v.last().map_or_else(BigRational::zero, Signed::signum);
+            if x < 0. && v.len() % 2 == 0 {
+                -lead
+            } else {
+                lead
+            }
+        })),
+    }
+}
+
+fn eval_ratios(v: &[BigRational], x: &BigRational) -> BigRational {
+    v.iter()
+        .rev()
+        .fold(BigRational::zero(), |acc, c| acc * x + c)
+}
+
+// Zeros are skipped, as in Descartes' rule of signs.
+fn sign_changes<T: Signed>(signs: impl Iterator<Item = T>) -> i32 {
+    signs
+        .filter(|v| !v.is_zero())
+        .map_windows(|[a, b]| (a.is_negative() != b.is_negative()) as i32)
+        .sum()
+}
+
+/// The gcd of two polynomials with coefficients modulo `MODULUS`, up to a constant factor.
+fn gcd_modular(mut r0: Vec<u64>, mut r1: Vec<u64>) -> Vec<u64> {
+    let trim = |v: &mut Vec<u64>| {
+        while v.last() == Some(&0) {
+            v.pop();
+        }
+    };
+    trim(&mut r1);
+
+    while let Some(&lead) = r1.last() {
+        // By Fermat's little theorem, lead^(MODULUS - 2) is the inverse of lead.
+        let inv = (0..64).rev().fold(1, |acc, bit| {
+            let acc = acc * acc % MODULUS;
+            if (MODULUS - 2) >> bit & 1 == 1 {
+                acc * lead % MODULUS
+            } else {
+                acc
+            }
+        });
+
+        while r0.len() >= r1.len() {
+            let shift = r0.len() - r1.len();
+            let factor = r0.last().unwrap() * inv % MODULUS;
+            for (i, c) in r1.iter().enumerate() {
+                r0[shift + i] = (r0[shift + i] + MODULUS - factor * c % MODULUS) % MODULUS;
+            }
+            trim(&mut r0);
+        }
+
+        core::mem::swap(&mut r0, &mut r1);
+    }
+
+    r0
+}
+
+fn divisors(n: u64) -> Vec<u64> {
+    let mut res: Vec<_> = (1..)
+        .take_while(|d| d * d <= n)
+        .filter(|d| n.is_multiple_of(*d))
+        .flat_map(|d| [d, n / d])
+        .collect();
+    res.sort_unstable();
+    res.dedup();
+    res
+}
+
+fn primitive(v: &mut [BigRational]) -> BigRational {
+    let mut d = v
+        .iter()
+        .fold(BigRational::zero(), |acc, v| gcd(acc, v.clone()));
+    if v.last().unwrap().is_negative() != d.is_negative() {
+        d = -d;
+    }
+
+    v.iter_mut().for_each(|v| *v /= &d);
+
+    return d;
+
+    fn gcd(mut a: BigRational, mut b: BigRational) -> BigRational {
+        if a < b {
+            core::mem::swap(&mut a, &mut b);
+        }
+
+        while !b.is_zero() {
+            let rem = &a % &b;
+            a = b;
+            b = rem;
+        }
+
+        a
+    }
+}
+
+/// Parses a number or a fraction of numbers, `None` if it's neither or divides by zero.
+pub fn parse_coef(v: &str) -> Option<f64> {
+    // Underscores group digits, as in `1_000`.
+    let v = &v.replace('_', "");
+    let Some((numer, denom)) = v.split_once('/') else {
+        return v.parse().ok();
+    };
+
+    let (numer, denom): (f64, f64) = (numer.parse().ok()?, denom.parse().ok()?);
+    (denom != 0.).then(|| numer / denom)
+}
+
+fn divides_by_zero(v: &str) -> bool {
+    v.replace('_', "")
+        .split_once('/')
+        .is_some_and(|(numer, denom)| numer.parse::<f64>().is_ok() && denom.parse() == Ok(0.))
+}
+
+pub fn tokenize(s: &str) -> impl Iterator<Item = &str> {
+    s.split(|c: char| c.is_whitespace() || c == ',' || c == ';')
+        .filter(|t| !t.is_empty())
+}
+
+pub fn parse_coefs(
+    iter: impl Iterator<Item = impl AsRef<str>>,
+    ascending: bool,
+) -> Result<Vec<f64>, Error> {
+    let mut coefs = iter
+        .enumerate()
+        .map(|(index, v)| match parse_coef(v.as_ref()) {
+            Some(c) if c.is_finite() => Ok(c),
+            Some(_) => Err(Error::NonFinite { index }),
+            None if divides_by_zero(v.as_ref()) => Err(Error::DivisionByZero {
+                index,
+                token: v.as_ref().into(),
+            }),
+            None => Err(Error::Parse {
+                index,
+                token: v.as_ref().into(),
+            }),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if !ascending {
+        coefs.reverse();
+    }
+
+    Ok(coefs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn test_terms() {
+        let a: Polynomial = [1., 0., 0., 0., 0., 1.].into();
+        assert_eq!(a.terms().collect::<Vec<_>>(), [(0, 1.), (5, 1.)]);
+        assert_eq!(a.terms_desc().collect::<Vec<_>>(), [(5, 1.), (0, 1.)]);
+        assert_eq!(Polynomial::ZERO.terms().count(), 0);
+
+        let a: Polynomial = [-1., 0., 2.5].into();
+        assert_eq!(a.to_string(), "2.5x^2-1");
+    }
+
+    #[test]
+    fn test_coefficients() {
+        let p: Polynomial = [3., 0., -1., 0.].into();
+        assert_eq!(p.coefficients(), [3., 0., -1.]);
+        assert_eq!(Polynomial::from(p.clone().into_coefficients()), p);
+        assert!(Polynomial::ZERO.coefficients().is_empty());
+    }
+
+    #[test]
+    fn test_derivative_n() {
+        let a: Polynomial = [1., -2., 3., 5., -1., 2.].into();
+        assert_eq!(a.derivative_n(0), a);
+        assert_eq!(a.derivative_n(1), a.derivative());
+        assert_eq!(a.derivative_n(2), a.derivative().derivative());
+        assert_eq!(a.derivative_n(3), [30., -24., 120.].into());
+        assert_eq!(a.derivative_n(5), [240.].into());
+        assert_eq!(a.derivative_n(6), Polynomial::ZERO);
+        assert_eq!(Polynomial::ZERO.derivative_n(2), Polynomial::ZERO);
+    }
+
+    #[test]
+    fn test_horner() {
+        let (res, rem) = Polynomial::from([2., 1., -2., 8.]).div_rem(&[-1., 2.].into());
+        assert_eq!(res, [1., 1., 4.].into());
+        assert_eq!(rem, [3.].into());
+    }
+
+    #[test]
+    fn test_long_div() {
+        let (res, rem) = Polynomial::from([2., 1., 0., 2., 1.]).div_rem(&[1., 1., 1.].into());
+        assert_eq!(res, [-2., 1., 1.].into());
+        assert_eq!(rem, [4., 2.].into());
+
+        let (res, rem) = Polynomial::from([1., 0., 1., 0., 1., 1.]).div_rem(&[1., 0., 1.].into());
+        assert_eq!(res, [0., -1., 1., 1.].into());
+        assert_eq!(rem, [1., 1.].into());
+
+        let (res, rem) = Polynomial::from([1., 2., 3., 2., 1.]).div_rem(&[1., 1., 1.].into());
+        assert_eq!(res, [1., 1., 1.].into());
+        assert_eq!(rem, Polynomial::ZERO);
+    }
+
+    #[test]
+    fn test_div_rem_operators() {
+        let a: Polynomial = [2., 1., 0., 2., 1.].into();
+        let b: Polynomial = [1., 1., 1.].into();
+        assert_eq!(&a / &b, [-2., 1., 1.].into());
+        assert_eq!(&a % &b, [4., 2.].into());
+        assert_eq!(a.quotient(&b), [-2., 1., 1.].into());
+        assert_eq!(a.remainder(&b), [4., 2.].into());
+
+        let a: Polynomial = [1., 0., 1., 0., 1., 1.].into();
+        let b: Polynomial = [1., 0., 1.].into();
+        assert_eq!(a.clone() / b.clone(), [0., -1., 1., 1.].into());
+        assert_eq!(a % b, [1., 1.].into());
+
+        let a: Polynomial = [1., 2., 3., 2., 1.].into();
+        assert_eq!(&a % &[1., 1., 1.].into(), Polynomial::ZERO);
+    }
+
+    #[test]
+    fn test_gcd() {
+        let res = Polynomial::from([0., -2., 1.]).gcd(&[-4., -2., 0., 1.].into());
+        assert_eq!(res, [-2., 1.].into());
+
+        let res = Polynomial::from([4., -3., 1., -3., 1.]).gcd(&[-1., 0., 0., 1.].into());
+        assert_eq!(res, [-1., 1.].into());
+
+        let res = Polynomial::from([1., 1., 1.]).gcd(&[1., 2., 1.].into());
+        assert_eq!(res, [1.].into());
+    }
+
+    #[test]
+    fn test_gsfd() {
+        let a: Polynomial = [1., 2., 1.].into();
+        assert_eq!(a.gsfd(), [1., 1.].into());
+
+        let a: Polynomial = [1., 2., 3., 2., 1.].into();
+        assert_eq!(a.gsfd(), [1., 1., 1.].into());
+
+        let a: Polynomial = [1875., -2000., -1025., 640., 425., 80., 5.].into(); // 5(x-1)^2(x+3)(x+5)^3
+        assert_eq!(a.gsfd(), [-15., 7., 7., 1.].into()); // (x-1)(x+3)(x+5)
+    }
+
+    #[test]
+    fn test_monic() {
+        let a: Polynomial = [6., -10., 2.].into(); // 2(x^2-5x+3)
+        assert!(!a.is_monic());
+
+        let m = a.monic();
+        assert!(m.is_monic());
+        assert_eq!(m.lead(), 1.);
+        assert_eq!(m, [3., -5., 1.].into());
+
+        let a: Polynomial = [-6., 3.].into();
+        assert_eq!(a.monic()(2.), 0.);
+
+        assert!(!Polynomial::ZERO.is_monic());
+        assert_eq!(Polynomial::ZERO.monic(), Polynomial::ZERO);
+    }
+
+    #[test]
+    fn test_squarefree_factorization() {
+        let a: Polynomial = [1875., -2000., -1025., 640., 425., 80., 5.].into(); // 5(x-1)^2(x+3)(x+5)^3
+        assert_eq!(
+            a.squarefree_factorization(),
+            [
+                ([3., 1.].into(), 1),
+                ([-1., 1.].into(), 2),
+                ([5., 1.].into(), 3)
+            ]
+        );
+
+        let a: Polynomial = [-2., 0., 1.].into();
+        assert_eq!(a.squarefree_factorization(), [(a, 1)]);
+
+        let a = Polynomial::from_roots(&[(1., 2), (2., 2)]);
+        assert_eq!(a.squarefree_factorization(), [([2., -3., 1.].into(), 2)]);
+
+        assert!(Polynomial::from([3.]).squarefree_factorization().is_empty());
+    }
+
+    #[test]
+    fn test_is_squarefree() {
+        assert!(Polynomial::from([-1., 0., 1.]).is_squarefree());
+        assert!(!Polynomial::from([1., -2., 1.]).is_squarefree());
+        assert!(!Polynomial::from_roots(&[(0.5, 1), (-0.25, 2)]).is_squarefree());
+        assert!(Polynomial::from([1., 0., 1.]).is_squarefree());
+        assert!(Polynomial::from([7.]).is_squarefree());
+    }
+
+    #[test]
+    fn test_is_squarefree_modulo_prime() {
+        assert!(Polynomial::from_roots(&[(1., 1), (-0.5, 1), (3., 1)]).is_squarefree_modulo_prime());
+        assert!(!Polynomial::from_roots(&[(1., 2), (-0.5, 1)]).is_squarefree_modulo_prime());
+        assert!(Polynomial::from([0.1, 0.7, -1e-9, 3.3]).is_squarefree_modulo_prime());
+        assert!(Polynomial::from([5.]).is_squarefree_modulo_prime());
+    }
+
+    #[test]
+    fn test_rationals() {
+        let r = |n, d| Rational32::new(n, d);
+
+        let p = Polynomial::from([-6., 11., -6., 1.]);
+        let v = p.to_rationals().unwrap();
+        assert_eq!(v, [r(-6, 1), r(11, 1), r(-6, 1), r(1, 1)]);
+        assert_eq!(Polynomial::from_rationals(v), p);
+
+        let p = Polynomial::from([0.375, -2.5, 0.]);
+        let v = p.to_rationals().unwrap();
+        assert_eq!(v, [r(3, 8), r(-5, 2)]);
+        assert_eq!(Polynomial::from_rationals(v), p);
+
+        assert_eq!(
+            Polynomial::from_rationals(vec![r(1, 3), r(0, 1)]),
+            Polynomial::from([1. / 3.])
+        );
+        assert_eq!(Polynomial::from_rationals(vec![]), Polynomial::ZERO);
+        assert_eq!(Polynomial::ZERO.to_rationals(), Ok(vec![]));
+
+        assert_eq!(
+            Polynomial::from([1., 0.1]).to_rationals(),
+            Err(Error::NotRational32 { index: 1 })
+        );
+        assert_eq!(
+            Polynomial::from([2f64.powi(40)]).to_rationals(),
+            Err(Error::NotRational32 { index: 0 })
+        );
+    }
+
+    #[test]
+    fn test_monic_rational() {
+        let r = |n, d| Rational32::new(n, d);
+        assert_eq!(
+            Polynomial::from([-4., 0., 2.]).monic_rational(),
+            Some(vec![r(-2, 1), r(0, 1), r(1, 1)])
+        );
+        assert_eq!(
+            Polynomial::from([1., 3.]).monic_rational(),
+            Some(vec![r(1, 3), r(1, 1)])
+        );
+        assert_eq!(Polynomial::from([0.1, 1.]).monic_rational(), None);
+        assert_eq!(Polynomial::from([1., 2f64.powi(40)]).monic_rational(), None);
+        assert_eq!(Polynomial::ZERO.monic_rational(), Some(vec![]));
+    }
+
+    #[test]
+    fn test_primitive() {
+        let a: Polynomial = [2., -4., -4.].into();
+        assert_eq!(a.primitive(), ([-1., 2., 2.].into(), -2.));
+        assert_eq!(a.content(), -2.);
+        assert_eq!(a.primitive_part(), [-1., 2., 2.].into());
+
+        let a: Polynomial = [3e20, 6e20, -9e20].into();
+        assert_eq!(a.content(), -3e20);
+        assert_eq!(a.primitive_part(), [-1., -2., 3.].into());
+
+        let a: Polynomial = [0.5, 1.5].into();
+        assert_eq!(a.content(), 0.5);
+        assert_eq!(a.primitive_part(), [1., 3.].into());
+
+        assert_eq!(Polynomial::ZERO.content(), 0.);
+        assert_eq!(Polynomial::ZERO.primitive_part(), Polynomial::ZERO);
+    }
+
+    #[test]
+    fn test_big_coefficients() {
+        let a: Polynomial = [-1e7, 1. - 1e7, 1.].into(); // (x-1e7)(x+1)
+        assert_eq!(a.gcd(&[-1e7, 1.].into()), [-1e7, 1.].into());
+
+        let a: Polynomial = [3e7, -3., -1e7, 1.].into(); // (x-1e7)(x^2-3)
+        assert_eq!(a.gsfd(), a);
+    }
+
+    #[test]
+    fn test_add() {
+        let a: Polynomial = [1., 2., 3.].into();
+        assert_eq!(&a + &[1., 1.].into(), [2., 3., 3.].into());
+        assert_eq!(&a + &[0., 0., -3.].into(), [1., 2.].into());
+        assert_eq!(&a + &Polynomial::ZERO, a);
+    }
+
+    #[test]
+    fn test_mul() {
+        let a: Polynomial = [-1., 1.].into();
+        assert_eq!(&a * &[1., 1.].into(), [-1., 0., 1.].into());
+        assert_eq!(a * Polynomial::ZERO, Polynomial::ZERO);
+    }
 
-    if rhs[1] != ONE {
-        lhs.iter_mut().for_each(|v| *v /= rhs[1]);
+    #[test]
+    #[should_panic(expected = "multiplicities are not negative")]
+    fn test_from_roots_negative_multiplicity() {
+        Polynomial::from_roots(&[(2., -1)]);
     }
 
-    (lhs, rem)
-}
+    #[test]
+    fn test_into_iter() {
+        let p = Polynomial::from([1., 0., -2.5]);
 
-fn long_div(mut lhs: Vec<Rational32>, rhs: &[Rational32]) -> (Vec<Rational32>, Vec<Rational32>) {
-    let init_l_grade = lhs.len() - 1;
-    let init_r_grade = rhs.len() - 1;
-    if init_l_grade < init_r_grade {
-        return (vec![], lhs);
+        let mut terms = vec![];
+        for (deg, c) in &p {
+            terms.push((deg, c));
+        }
+        assert_eq!(terms, [(0, 1.), (1, 0.), (2, -2.5)]);
+        assert!((&p).into_iter().eq(p.iter()));
+        assert_eq!(p.into_iter().collect::<Vec<_>>(), terms);
+        assert_eq!(Polynomial::ZERO.into_iter().count(), 0);
     }
 
-    let res_g = init_l_grade - init_r_grade;
-    let mut res = vec![ZERO; res_g + 1];
+    #[test]
+    fn test_sum_product() {
+        let ps: [Polynomial; 3] = [[1., 2.].into(), [0., 0., 3.].into(), [-1.].into()];
+        assert_eq!(ps.iter().sum::<Polynomial>(), [0., 2., 3.].into());
+        assert_eq!(
+            ps.into_iter().product::<Polynomial>(),
+            [0., 0., -3., -6.].into()
+        );
 
-    while lhs.len() >= rhs.len() {
-        let l_g = lhs.len() - 1;
-        let r_g = rhs.len() - 1;
-        let c = lhs[l_g] / rhs[r_g];
+        assert_eq!(
+            core::iter::empty::<Polynomial>().sum::<Polynomial>(),
+            Polynomial::ZERO
+        );
+        assert_eq!(
+            core::iter::empty::<Polynomial>().product::<Polynomial>(),
+            [1.].into()
+        );
 
-        (0..=r_g).for_each(|k| lhs[l_g - k] -= c * rhs[r_g - k]);
+        let product: Polynomial = [1., 2.]
+            .iter()
+            .map(|&r| Polynomial::from([-r, 1.]))
+            .product();
+        assert_eq!(product, Polynomial::from_roots(&[(1., 1), (2., 1)]));
+    }
 
-        while let Some(v) = lhs.last() {
-            if *v != ZERO {
-                break;
-            }
+    #[test]
+    fn test_pow() {
+        let a: Polynomial = [1., 1.].into();
+        assert_eq!(a.pow(0), [1.].into());
+        assert_eq!(a.pow(1), a);
+        assert_eq!(a.pow(3), [1., 3., 3., 1.].into());
+
+        let a: Polynomial = [-1., 1.].into();
+        assert_eq!(a.pow(5), [-1., 5., -10., 10., -5., 1.].into());
+    }
 
-            lhs.pop();
+    #[test]
+    fn test_interpolate() {
+        let p: Polynomial = [2., -3., 1.].into();
+        let points: Vec<_> = [0., 1., 4.].into_iter().map(|x| (x, p(x))).collect();
+        assert_eq!(Polynomial::interpolate(&points).unwrap(), p);
+
+        let points = [(1., 2.), (2., 2.)];
+        assert_eq!(Polynomial::interpolate(&points).unwrap(), [2.].into());
+        assert_eq!(Polynomial::interpolate(&[]).unwrap(), Polynomial::ZERO);
+
+        assert_eq!(
+            Polynomial::interpolate(&[(1., 2.), (1., 3.)]),
+            Err(Error::DuplicatePoint { x: 1. })
+        );
+    }
+
+    #[test]
+    fn test_from_roots() {
+        assert_eq!(Polynomial::from_roots(&[]), [1.].into());
+        assert_eq!(
+            Polynomial::from_roots(&[(1., 1), (2., 1)]),
+            [2., -3., 1.].into()
+        );
+        assert_eq!(Polynomial::from_roots(&[(-1., 3)]), [1., 3., 3., 1.].into());
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let roots: Vec<_> = (0..rng.gen_range(1..5))
+                .map(|_| (rng.gen_range(-10..10) as f64, rng.gen_range(1..3)))
+                .collect();
+            let p = Polynomial::from_roots(&roots);
+
+            assert_eq!(p.grade(), roots.iter().map(|r| r.1).sum());
+            assert_eq!(p.lead(), 1.);
+            assert!(roots.iter().all(|&(r, _)| p(r) == 0.));
         }
+    }
 
-        res[res_g - (init_l_grade - l_g)] = c;
+    #[test]
+    fn test_scale_input() {
+        let a: Polynomial = [2., -3., 1.].into(); // (x-1)(x-2)
+        assert_eq!(a.scale_input(2.), [8., -6., 1.].into()); // (x-2)(x-4)
+        assert_eq!(a.scale_input(-1.), [2., 3., 1.].into()); // (x+1)(x+2)
+        assert_eq!(Polynomial::ZERO.scale_input(3.), Polynomial::ZERO);
     }
 
-    (res, lhs)
-}
+    #[test]
+    fn test_shift() {
+        let a: Polynomial = [2., -3., 1.].into(); // (x-1)(x-2)
+        assert_eq!(a.shift(1.), [6., -5., 1.].into()); // (x-2)(x-3)
+        assert_eq!(a.shift(-1.), [0., -1., 1.].into()); // x(x-1)
+
+        let a: Polynomial = [-1., 3., -3., 1.].into(); // (x-1)^3
+        assert_eq!(a.shift(-1.), [0., 0., 0., 1.].into());
+        assert_eq!(Polynomial::ZERO.shift(1.), Polynomial::ZERO);
+    }
 
-fn div(mut lhs: Vec<Rational32>, rhs: &[Rational32]) -> (Vec<Rational32>, Vec<Rational32>) {
-    match rhs.len() {
-        0 => panic!("Division by 0"),
-        1 => {
-            lhs.iter_mut().for_each(|v| *v /= rhs[0]);
-            (lhs, vec![])
+    #[test]
+    fn test_depress() {
+        // (x - 1)(x - 2)(x - 6)
+        let p = Polynomial::from_roots(&[(1., 1), (2., 1), (6., 1)]);
+        let (q, s) = p.depress();
+        assert_eq!(s, -3.);
+        assert_eq!(q, [-6., -7., 0., 1.].into());
+        assert_eq!(q[2], 0.);
+        for x in [1., 2., 6.] {
+            assert_eq!(q.eval_compensated(x + s), 0.);
         }
-        2 => {
-            let (res, rem) = horner_div(lhs, rhs);
-            (res, if rem == ZERO { vec![] } else { vec![rem] })
+
+        let (q, s) = Polynomial::from([1., 0.5, 1., 3.]).depress();
+        assert_eq!(q.grade(), 3);
+        assert_eq!(q[2], 0.);
+        assert_eq!(s, 1. / 9.);
+
+        assert_eq!(
+            Polynomial::from([5.]).depress(),
+            (Polynomial::from([5.]), 0.)
+        );
+        assert_eq!(Polynomial::ZERO.depress(), (Polynomial::ZERO, 0.));
+    }
+
+    #[test]
+    fn test_descartes_bounds() {
+        let a: Polynomial = [1., -1., -1., 1.].into();
+        assert_eq!(a.sign_variations(), 2);
+        assert_eq!(a.descartes_bounds(), (2, 1));
+
+        let a: Polynomial = [1., 0., 0., 0., 1.].into();
+        assert_eq!(a.descartes_bounds(), (0, 0));
+        assert_eq!(Polynomial::ZERO.descartes_bounds(), (0, 0));
+    }
+
+    #[test]
+    fn test_round_coefficients() {
+        let a: Polynomial = [-0.0000000001, 2.4, 1.9999999999].into();
+        assert_eq!(a.round_coefficients(1e-9), [0., 2.4, 2.].into());
+        assert_eq!(a.round_coefficients(1e-12), a);
+
+        let a: Polynomial = [1., 1e-12].into();
+        assert_eq!(a.round_coefficients(1e-9), [1.].into());
+    }
+
+    #[test]
+    fn test_eval_compensated() {
+        let roots: Vec<_> = (1..=20).map(|r| (r as f64, 1)).collect();
+        let p = Polynomial::from_roots(&roots);
+
+        for x in [10.0001, 20.001] {
+            let x_r = BigRational::from_f64(x).unwrap();
+            let exact = p
+                .to_ratios()
+                .iter()
+                .rev()
+                .fold(BigRational::zero(), |acc, c| acc * &x_r + c);
+            let exact = ratio_to_f64(&exact);
+
+            let plain_err = ((p(x) - exact) / exact).abs();
+            let compensated_err = ((p.eval_compensated(x) - exact) / exact).abs();
+
+            assert!(compensated_err < 1e-12);
+            assert!(compensated_err < plain_err);
         }
-        _ => long_div(lhs, rhs),
+
+        assert_eq!(Polynomial::ZERO.eval_compensated(1.), 0.);
     }
-}
 
-fn gcd(mut r0: Vec<Rational32>, mut r1: Vec<Rational32>) -> Vec<Rational32> {
-    if r0.len() < r1.len() {
-        std::mem::swap(&mut r0, &mut r1);
+    #[test]
+    fn test_eval_complex() {
+        let p: Polynomial = [1., 0., 1.].into();
+        assert_eq!(p.eval_complex(0., 1.), (0., 0.));
+        assert_eq!(p.eval_complex(1., 1.), (1., 2.));
+        assert_eq!(p.eval_complex(3., 0.), (p(3.), 0.));
+        assert_eq!(Polynomial::ZERO.eval_complex(1., 1.), (0., 0.));
     }
 
-    while !r1.is_empty() {
-        let (_, rem) = div(r0, &r1);
-        r0 = r1;
-        r1 = rem;
+    #[test]
+    fn test_eval_interval() {
+        let a: Polynomial = [-2., 0., 1.].into();
+
+        let (lo, hi) = a.eval_interval(1.4, 1.5);
+        assert!(lo < 0. && 0. < hi);
+
+        let (lo, hi) = a.eval_interval(2., 3.);
+        assert!(lo <= 2. && 7. <= hi && 0. < lo);
+
+        let (lo, hi) = a.eval_interval(-1., 1.);
+        assert!(lo <= -2. && (-1. ..0.).contains(&hi));
+
+        // The products round too, not only the sums.
+        let encloses = |p: &Polynomial, x: f64| {
+            let (lo, hi) = p.eval_interval(x, x);
+            let exact = eval_ratios(&p.to_ratios(), &BigRational::from_f64(x).unwrap());
+            BigRational::from_f64(lo).unwrap() <= exact
+                && exact <= BigRational::from_f64(hi).unwrap()
+        };
+        let p = Polynomial::from([
+            -0.29284297007625004,
+            0.8489189752354773,
+            0.18550114227712866,
+            0.9780691497694114,
+        ]);
+        assert!(encloses(&p, 0.28136584575885837));
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..200 {
+            let p = Polynomial::from((0..6).map(|_| rng.gen_range(-1. ..1.)).collect::<Vec<_>>());
+            assert!(encloses(&p, rng.gen_range(-2. ..2.)));
+        }
     }
 
-    primitive(&mut r0);
+    #[test]
+    fn test_reciprocal() {
+        let a: Polynomial = [6., -5., 1.].into();
+        assert_eq!(a.reciprocal(), [1., -5., 6.].into());
+        assert_eq!(a.reciprocal().reciprocal(), a);
+
+        let a: Polynomial = [0., 0., 1., 1.].into(); // x^2(x+1)
+        assert_eq!(a.reciprocal(), [1., 1.].into());
+        assert_eq!(Polynomial::ZERO.reciprocal(), Polynomial::ZERO);
+    }
 
-    r0
-}
+    #[test]
+    fn test_graeffe_magnitudes() {
+        let a = Polynomial::from_roots(&[(1., 1), (10., 1), (100., 1)]);
+        let m = a.graeffe_magnitudes(5);
+        assert_eq!(m.len(), 3);
+        for (m, e) in m.into_iter().zip([1., 10., 100.]) {
+            assert!((m - e).abs() < 1e-3 * e, "{m} != {e}");
+        }
 
-fn primitive(v: &mut [Rational32]) -> Rational32 {
-    let mut d = v.iter().fold(ZERO, |acc, &v| gcd(acc, v));
-    if opposite_signs(v.last().unwrap(), &d) {
-        d = -d;
+        let a: Polynomial = [0., 4., 0., 1.].into(); // x(x^2+4)
+        let m = a.graeffe_magnitudes(8);
+        assert_eq!(m[0], 0.);
+        assert!(m[1..].iter().all(|m| (m - 2.).abs() < 1e-2), "{m:?}");
+
+        assert!(Polynomial::ZERO.graeffe_magnitudes(3).is_empty());
+
+        // Squaring takes the coefficients far outside the range of f64.
+        let a = Polynomial::from([0.5, -3.1, 0., 0., 1e21, 1.]);
+        let m = a.graeffe_magnitudes(4);
+        assert_eq!(m.len(), 5);
+        assert!(m[..4].iter().all(|&m| m > 1e-6 && m < 1e-5), "{m:?}");
+        assert!((m[4] - 1e21).abs() < 1e-3 * 1e21);
+
+        let roots: Vec<_> = (0..20)
+            .map(|k| {
+                (
+                    if k % 2 == 0 { 1. } else { -1. } * (2e4 + 400. * k as f64),
+                    1,
+                )
+            })
+            .collect();
+        let m = Polynomial::from_roots(&roots).graeffe_magnitudes(4);
+        assert_eq!(m.len(), 20);
+        assert!(m.iter().all(|&m| m > 1e4 && m < 4e4), "{m:?}");
     }
 
-    v.iter_mut().for_each(|v| *v /= d);
+    #[test]
+    fn test_resultant() {
+        let a: Polynomial = [-1., 1.].into();
+        assert_eq!(a.resultant(&[-2., 1.].into()), -1.);
+        assert_eq!(a.resultant(&[1., 0., 1.].into()), 2.);
+
+        let a: Polynomial = [2., -3., 1.].into(); // (x-1)(x-2)
+        assert_eq!(a.resultant(&[-3., 2., 1.].into()), 0.); // (x-1)(x+3)
+        assert_eq!(a.resultant(&Polynomial::ZERO), 0.);
+    }
 
-    return d;
+    #[test]
+    fn test_shares_root_with() {
+        let a: Polynomial = [2., -3., 1.].into(); // (x-1)(x-2)
+        assert!(a.shares_root_with(&[-3., 2., 1.].into())); // (x-1)(x+3)
+        assert!(!a.shares_root_with(&[12., -7., 1.].into())); // (x-3)(x-4)
+        assert!(!a.shares_root_with(&[1., 0., 1.].into()));
+
+        // x^2 + 1 and x^3 + x^2 + x + 1 share ±i.
+        let b: Polynomial = [1., 0., 1.].into();
+        assert!(b.shares_root_with(&[1., 1., 1., 1.].into()));
+
+        // The resultant of 1e-200(x-1) and 1e-200(x-2) is -1e-400, which rounds to 0.
+        let c: Polynomial = [-1e-200, 1e-200].into();
+        let d: Polynomial = [-2e-200, 1e-200].into();
+        assert_eq!(c.resultant(&d), 0.);
+        assert!(!c.shares_root_with(&d));
+
+        assert!(!a.shares_root_with(&[5.].into()));
+        assert!(a.shares_root_with(&Polynomial::ZERO));
+        assert!(!Polynomial::ZERO.shares_root_with(&[5.].into()));
+    }
 
-    fn gcd(mut a: Rational32, mut b: Rational32) -> Rational32 {
-        if a < b {
-            std::mem::swap(&mut a, &mut b);
+    #[test]
+    fn test_from_str() {
+        let p: Polynomial = "1 -3 2".parse().unwrap();
+        assert_eq!(p, [2., -3., 1.].into());
+        assert_eq!(
+            "1/2, 0; -1".parse::<Polynomial>().unwrap(),
+            [-1., 0., 0.5].into()
+        );
+        assert_eq!("".parse::<Polynomial>().unwrap(), Polynomial::ZERO);
+        assert_eq!("0".parse::<Polynomial>().unwrap(), Polynomial::ZERO);
+
+        assert_eq!(
+            "1 x 2".parse::<Polynomial>(),
+            Err(Error::Parse {
+                index: 1,
+                token: "x".into()
+            })
+        );
+        assert_eq!(
+            "1 NaN".parse::<Polynomial>(),
+            Err(Error::NonFinite { index: 1 })
+        );
+        assert_eq!(
+            "1/0 2".parse::<Polynomial>(),
+            Err(Error::DivisionByZero {
+                index: 0,
+                token: "1/0".into()
+            })
+        );
+        assert_eq!(
+            "1_000 1.5e3 -2E-4 1_0.5e-1_0"
+                .parse::<Polynomial>()
+                .unwrap(),
+            [1.05e-9, -2e-4, 1500., 1000.].into()
+        );
+        assert_eq!(
+            "1 _".parse::<Polynomial>(),
+            Err(Error::Parse {
+                index: 1,
+                token: "_".into()
+            })
+        );
+        assert_eq!(
+            "1 1e400".parse::<Polynomial>().unwrap_err().to_string(),
+            "coefficient 2 is not a finite number"
+        );
+    }
+
+    #[test]
+    fn test_map_coefficients() {
+        use crate::roots::{find_roots, sort_roots};
+
+        let values = |p: &Polynomial| {
+            let mut roots = find_roots(p).unwrap();
+            sort_roots(&mut roots);
+            roots.iter().map(|r| r.value).collect::<Vec<_>>()
+        };
+
+        let p = Polynomial::from_roots(&[(1., 1), (-2., 1), (3., 1)]);
+        let negated = p.map_coefficients(|v| -v);
+        assert_eq!(negated, [-6., 5., 2., -1.].into());
+        assert_eq!(values(&negated), values(&p));
+
+        // Flipping the sign of every other coefficient negates the roots instead.
+        let mirrored = p.scale_input(-1.);
+        for (a, b) in values(&mirrored).iter().zip(values(&p).iter().rev()) {
+            assert!((a + b).abs() < 1e-12);
         }
 
-        while b != ZERO {
-            let rem = a % b;
-            a = b;
-            b = rem;
+        assert_eq!(p.map_coefficients(|_| 0.), Polynomial::ZERO);
+        assert_eq!(
+            Polynomial::from([1., 2., 0.5]).map_coefficients(f64::floor),
+            [1., 2.].into()
+        );
+    }
+
+    #[test]
+    fn test_to_string_with_var() {
+        let p: Polynomial = [1., 0., 1.].into();
+        assert_eq!(p.to_string_with_var("t"), "t^2+1");
+        assert_eq!(p.to_string_with_var("x"), p.to_string());
+        assert_eq!(
+            Polynomial::from([0., -2., 0., 3.]).to_string_with_var("z"),
+            "3z^3-2z"
+        );
+        assert_eq!(Polynomial::ZERO.to_string_with_var("t"), "0");
+    }
+
+    #[test]
+    fn test_neg() {
+        let p: Polynomial = [0., -2., 0., 3.].into();
+        let neg = -&p;
+        assert_eq!(neg, [0., 2., 0., -3.].into());
+        assert!(neg.iter().all(|(_, v)| v.is_sign_positive() || v != 0.));
+
+        assert_eq!(-p.clone() + p.clone(), Polynomial::ZERO);
+        assert_eq!(&p - &p, Polynomial::ZERO);
+        assert_eq!(p - [1., 1.].into(), [-1., -3., 0., 3.].into());
+        assert_eq!(-Polynomial::ZERO, Polynomial::ZERO);
+    }
+
+    #[test]
+    fn test_reflect_y() {
+        use crate::roots::{find_roots, sort_roots};
+
+        let p = Polynomial::from_roots(&[(1., 1), (-2., 1), (3., 1)]);
+        let reflected = p.reflect_y();
+        assert_eq!(
+            reflected,
+            -Polynomial::from_roots(&[(-1., 1), (2., 1), (-3., 1)])
+        );
+
+        let mut roots = find_roots(&reflected).unwrap();
+        sort_roots(&mut roots);
+        let values: Vec<_> = roots.iter().map(|r| r.value).collect();
+        assert_eq!(values, [-3., -1., 2.]);
+
+        let even: Polynomial = [4., 0., -5., 0., 1.].into();
+        assert_eq!(even.reflect_y(), even);
+        assert_eq!(Polynomial::ZERO.reflect_y(), Polynomial::ZERO);
+    }
+
+    #[test]
+    fn test_sample() {
+        let p: Polynomial = [-1., 0., 1.].into();
+        assert_eq!(
+            p.sample(-1., 1., 5),
+            [(-1., 0.), (-0.5, -0.75), (0., -1.), (0.5, -0.75), (1., 0.)]
+        );
+        assert_eq!(p.sample(2., 3., 1), [(2., 3.)]);
+        assert!(p.sample(2., 3., 0).is_empty());
+
+        let samples = p.sample(0., 0.3, 4);
+        assert_eq!(samples.len(), 4);
+        assert_eq!(samples[3].0, 0.3);
+    }
+
+    #[test]
+    fn test_count_sign_changes_in() {
+        let p: Polynomial = [-2., 0., 1.].into();
+        assert_eq!(p.count_sign_changes_in(1., 2.), Ok(1));
+        assert_eq!(p.count_sign_changes_in(-2., 2.), Ok(2));
+        assert_eq!(p.count_sign_changes_in(2., 3.), Ok(0));
+
+        let (inf, neg_inf) = (f64::INFINITY, f64::NEG_INFINITY);
+        assert_eq!(p.count_sign_changes_in(neg_inf, inf), Ok(2));
+        assert_eq!(p.count_sign_changes_in(0., inf), Ok(1));
+        assert_eq!(p.count_sign_changes_in(f64::NAN, 0.), Err(Error::NanBound));
+
+        // The double root at 1 doesn't change the sign.
+        let p = Polynomial::from_roots(&[(1., 2)]);
+        assert_eq!(p.count_sign_changes_in(0., 2.), Ok(0));
+    }
+
+    #[test]
+    fn test_divides() {
+        let a: Polynomial = [-1., 1.].into();
+        assert!(a.divides(&[-1., 0., 1.].into()));
+        assert!(!a.divides(&[1., 0., 1.].into()));
+        assert!(a.divides(&Polynomial::ZERO));
+        assert!(Polynomial::from([2.]).divides(&[1., 3.].into()));
+        assert!(!Polynomial::ZERO.divides(&a));
+    }
+
+    #[test]
+    fn test_horner_table() {
+        let p: Polynomial = [6., -5., 1.].into();
+        assert_eq!(p.horner_table(2.), [1., -3., 0.]);
+        assert_eq!(p.horner_table(1.), [1., -4., 2.]);
+        assert_eq!(Polynomial::ZERO.horner_table(1.), []);
+    }
+
+    #[test]
+    fn test_synthetic_deflate() {
+        let p = Polynomial::from_roots(&[(1., 1), (-2., 1), (3., 1)]);
+        assert_eq!(
+            p.synthetic_deflate(1.),
+            Polynomial::from_roots(&[(-2., 1), (3., 1)])
+        );
+        assert_eq!(
+            p.synthetic_deflate(1.).synthetic_deflate(-2.),
+            [-3., 1.].into()
+        );
+        assert_eq!(
+            Polynomial::from([5.]).synthetic_deflate(2.),
+            Polynomial::ZERO
+        );
+    }
+
+    #[test]
+    fn test_real_root_count() {
+        let p = Polynomial::from_roots(&[(1., 2), (-2., 1)]);
+        assert_eq!(p.real_root_count(false), 2);
+        assert_eq!(p.real_root_count(true), 3);
+
+        let q = &p * &[1., 0., 1.].into();
+        assert_eq!(q.real_root_count(false), 2);
+        assert_eq!(q.real_root_count(true), 3);
+        assert_eq!(Polynomial::from([3.]).real_root_count(true), 0);
+        assert_eq!(Polynomial::ZERO.real_root_count(false), 0);
+
+        // The root lies past f64::MAX, and so does the root bound.
+        let p = Polynomial::from([-1e300, 1e-10]);
+        assert_eq!(p.real_root_count(false), 1);
+    }
+
+    #[test]
+    fn test_sign_table() {
+        // (x + 2)(x - 0.5)(x - 3)
+        let p = Polynomial::from_roots(&[(-2., 1), (0.5, 1), (3., 1)]);
+        let rows = p.sign_table(&p.isolate_roots());
+        assert_eq!(rows.len(), 3);
+        for (row, root) in rows.iter().zip([-2., 0.5, 3.]) {
+            assert_ne!(row.sign_a, row.sign_b);
+            assert!(row.a < root && root <= row.b);
+            assert!((row.root - root).abs() < 1e-12, "{}", row.root);
         }
+        assert_eq!(rows[0].sign_a, Ordering::Less);
+        assert!(rows.windows(2).all(|w| w[0].sign_b != w[1].sign_b));
 
-        a
+        // A double root doesn't change the sign.
+        let p = Polynomial::from_roots(&[(1., 2), (-1., 1)]);
+        let rows = p.sign_table(&p.isolate_roots());
+        assert_eq!(rows[1].sign_a, rows[1].sign_b);
+        assert!((rows[1].root - 1.).abs() < 1e-12);
+
+        assert!(Polynomial::from([1.]).sign_table(&[(0., 1.)]).is_empty());
     }
 
-    fn opposite_signs(a: &Rational32, b: &Rational32) -> bool {
-        (*a.numer() ^ *b.numer()) < 0
+    #[test]
+    fn test_isolate_roots() {
+        let p = Polynomial::from_roots(&[(-3., 1), (0.5, 2), (0.75, 1), (4., 1)]);
+        let intervals = p.isolate_roots();
+        assert_eq!(intervals.len(), 4);
+        for ((a, b), r) in intervals.into_iter().zip([-3., 0.5, 0.75, 4.]) {
+            assert!(a < r && r <= b);
+        }
+
+        assert!(Polynomial::from([1., 0., 1.]).isolate_roots().is_empty());
+        assert!(Polynomial::from([2.]).isolate_roots().is_empty());
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_normalized() {
+        let p: Polynomial = [1., 0., 1e-300].into();
+        let results = [
+            p.div_rem(&[1e300].into()).0,
+            p.derivative_n(2).derivative(),
+            Polynomial::from([0., 1e-200]) * Polynomial::from([0., 1e-200]),
+            &p + &[0., 0., -1e-300].into(),
+            p.reciprocal(),
+            p.gcd(&[-1., 0., 1.].into()),
+            p.map_coefficients(|v| v - 1e-300),
+        ];
+
+        for q in results {
+            assert!(q == Polynomial::ZERO || q.lead() != 0., "{q:?}");
+        }
+    }
 
     #[test]
-    fn test_horner() {
-        let (res, rem) = Polynomial::from([2., 1., -2., 8.]).div_rem(&[-1., 2.].into());
-        assert_eq!(res, [1., 1., 4.].into());
-        assert_eq!(rem, [3.].into());
+    fn test_norms() {
+        let p: Polynomial = [3., 0., -4.].into();
+        assert_eq!(p.l1_norm(), 7.);
+        assert_eq!(p.l2_norm(), 5.);
+        assert_eq!(p.linf_norm(), 4.);
+
+        let big: Polynomial = [3e200, -4e200].into();
+        assert!((big.l2_norm() - 5e200).abs() <= 1e186);
+
+        assert_eq!(Polynomial::ZERO.l1_norm(), 0.);
+        assert_eq!(Polynomial::ZERO.l2_norm(), 0.);
+        assert_eq!(Polynomial::ZERO.linf_norm(), 0.);
     }
 
     #[test]
-    fn test_long_div() {
-        let (res, rem) = Polynomial::from([2., 1., 0., 2., 1.]).div_rem(&[1., 1., 1.].into());
-        assert_eq!(res, [-2., 1., 1.].into());
-        assert_eq!(rem, [4., 2.].into());
+    fn test_approx_eq() {
+        let p = Polynomial::from_roots(&[(0.1, 1), (0.2, 1), (0.3, 1)]);
+        let q: Polynomial = [-0.006, 0.11, -0.6, 1.].into();
+        assert_ne!(p, q);
+        assert!(p.approx_eq(&q, 1e-15));
+        assert!(!p.approx_eq(&q, 0.));
+
+        let r: Polynomial = [-0.006, 0.11, -0.6].into();
+        assert!(!q.approx_eq(&r, 0.5));
+        assert!(q.approx_eq(&r, 1.));
+        assert!(Polynomial::ZERO.approx_eq(&[1e-20].into(), 1e-15));
+    }
 
-        let (res, rem) = Polynomial::from([1., 0., 1., 0., 1., 1.]).div_rem(&[1., 0., 1.].into());
-        assert_eq!(res, [0., -1., 1., 1.].into());
-        assert_eq!(rem, [1., 1.].into());
+    #[test]
+    fn test_hash() {
+        #[derive(Default)]
+        struct Bytes(Vec<u8>);
 
-        let (res, rem) = Polynomial::from([1., 2., 3., 2., 1.]).div_rem(&[1., 1., 1.].into());
-        assert_eq!(res, [1., 1., 1.].into());
-        assert_eq!(rem, Polynomial::ZERO);
+        impl Hasher for Bytes {
+            fn finish(&self) -> u64 {
+                0
+            }
+
+            fn write(&mut self, bytes: &[u8]) {
+                self.0.extend_from_slice(bytes);
+            }
+        }
+
+        let hash = |p: Polynomial| {
+            let mut hasher = Bytes::default();
+            p.hash(&mut hasher);
+            hasher.0
+        };
+
+        let (a, b) = (Polynomial::from([-0., 1.]), Polynomial::from([0., 1.]));
+        assert_eq!(a, b);
+        assert_eq!(hash(a), hash(b));
+        assert_ne!(hash([1., 1.].into()), hash([0., 1.].into()));
+        assert_ne!(hash([1.].into()), hash([1., 1.].into()));
     }
 
     #[test]
-    fn test_gcd() {
-        let res = Polynomial::from([0., -2., 1.]).gcd(&[-4., -2., 0., 1.].into());
-        assert_eq!(res, [-2., 1.].into());
+    fn test_builder() {
+        let p = Polynomial::builder()
+            .term(100, 1.)
+            .term(0, 1.)
+            .build()
+            .unwrap();
+        assert_eq!(p.grade(), 100);
+        assert_eq!(p.terms().collect::<Vec<_>>(), [(0, 1.), (100, 1.)]);
+
+        let p = Polynomial::builder()
+            .term(3, 2.)
+            .term(0, -1.)
+            .term(3, 4.)
+            .term(5, 0.)
+            .build();
+        assert_eq!(p, Ok([-1., 0., 0., 4.].into()));
+
+        assert_eq!(
+            Polynomial::builder()
+                .term(1, 1.)
+                .term(4, f64::INFINITY)
+                .build(),
+            Err(Error::NonFinite { index: 1 })
+        );
+        assert_eq!(
+            Polynomial::builder().term(u32::MAX, 1.).build(),
+            Err(Error::TooManyCoefficients {
+                count: u32::MAX as usize + 1
+            })
+        );
+        assert_eq!(Polynomial::builder().build(), Ok(Polynomial::ZERO));
+    }
 
-        let res = Polynomial::from([4., -3., 1., -3., 1.]).gcd(&[-1., 0., 0., 1.].into());
-        assert_eq!(res, [-1., 1.].into());
+    #[test]
+    fn test_from() {
+        let coefs = vec![1., 2., 0.];
+        let expected = Polynomial::try_from_coefficients([1., 2.]).unwrap();
+
+        assert_eq!(Polynomial::from(&coefs[..]), expected);
+        assert_eq!(Polynomial::from([1., 2., 0.]), expected);
+        assert_eq!(Polynomial::from(&[1., 2., 0.]), expected);
+        assert_eq!(Polynomial::from(coefs), expected);
+    }
 
-        let res = Polynomial::from([1., 1., 1.]).gcd(&[1., 2., 1.].into());
-        assert_eq!(res, [1.].into());
+    #[test]
+    fn test_try_from_coefficients() {
+        assert_eq!(
+            Polynomial::try_from_coefficients([1., 2., 0.]),
+            Ok([1., 2.].into())
+        );
+        assert_eq!(
+            Polynomial::try_from_coefficients([1., f64::NAN]),
+            Err(Error::NonFinite { index: 1 })
+        );
+        assert_eq!(
+            Polynomial::try_from_coefficients(&[f64::NEG_INFINITY][..]),
+            Err(Error::NonFinite { index: 0 })
+        );
+
+        // Over i32::MAX coefficients can't be allocated in a test, so only the message is checked.
+        let err = Error::TooManyCoefficients { count: 1 << 31 };
+        assert_eq!(
+            err.to_string(),
+            "too many coefficients (2147483648), at most 2147483647 are supported"
+        );
     }
 
     #[test]
-    fn test_gsfd() {
-        let a: Polynomial = [1., 2., 1.].into();
-        assert_eq!(a.gsfd(), [1., 1.].into());
+    fn test_is_root() {
+        let p = Polynomial::from_roots(&[(2., 2), (-0.5, 1)]);
+        assert!(p.is_root(2.));
+        assert!(p.is_root(-0.5));
+        assert!(!p.is_root(0.));
+        assert!(Polynomial::from([-2., 0., 1.]).is_root(core::f64::consts::SQRT_2));
+
+        // The rounding error of p(x) scales with the coefficients, both large and small.
+        let large = Polynomial::from([-2e10, 0., 1.]);
+        assert!(large.is_root(2e10_f64.sqrt()));
+        assert!(large.is_root(-(2e10_f64.sqrt())));
+        assert!(!large.is_root(141421.));
+        let small = Polynomial::from([-1e-20, 1e-20]);
+        assert!(small.is_root(1.));
+        assert!(!small.is_root(2.));
+
+        assert_eq!(p.root_multiplicity_at(2.), 2);
+        assert_eq!(p.root_multiplicity_at(-0.5), 1);
+        assert_eq!(p.root_multiplicity_at(1.), 0);
+        assert_eq!(
+            Polynomial::from_roots(&[(0., 4)]).root_multiplicity_at(0.),
+            4
+        );
 
-        let a: Polynomial = [1., 2., 3., 2., 1.].into();
-        assert_eq!(a.gsfd(), [1., 1., 1.].into());
+        // Roots from a solver run with a looser tolerance are only recognized within that tolerance.
+        let cubic = Polynomial::from_roots(&[(1., 3)]);
+        assert!(!cubic.is_root(1. + 1e-4));
+        assert!(cubic.is_root_within(1. + 1e-4, 1e-3));
+        assert_eq!(cubic.root_multiplicity_at(1. + 1e-4), 0);
+        assert_eq!(cubic.root_multiplicity_at_within(1. + 1e-4, 1e-3), 3);
+    }
 
-        let a: Polynomial = [1875., -2000., -1025., 640., 425., 80., 5.].into(); // 5(x-1)^2(x+3)(x+5)^3
-        assert_eq!(a.gsfd(), [-15., 7., 7., 1.].into()); // (x-1)(x+3)(x+5)
+    #[test]
+    fn test_budan_fourier_count() {
+        let p: Polynomial = [1., -3., 0., 1.].into();
+        for (a, b) in [(0., 1.), (-3., 3.), (0., 2.), (1., 1.5), (-2., 0.)] {
+            assert_eq!(p.budan_fourier_count(a, b), p.count_roots_in(a, b).unwrap());
+        }
+        assert_eq!(p.count_roots_in(-3., 3.), Ok(3));
+        assert_eq!(p.count_roots_in(0., 1.), Ok(1));
+
+        // A root at either end is counted only at the right one.
+        let p: Polynomial = [1., -1.].into();
+        assert_eq!(p.budan_fourier_count(1., 2.), 0);
+        assert_eq!(p.budan_fourier_count(0., 1.), 1);
+        assert_eq!(p.count_roots_in(1., 2.), Ok(0));
+        let p = Polynomial::from_roots(&[(1., 2), (3., 1)]);
+        assert_eq!(p.budan_fourier_count(1., 2.), 0);
+        assert_eq!(p.budan_fourier_count(0., 1.), 2);
+
+        // Only an upper bound: x^2 + 1 has no real roots.
+        let p: Polynomial = [1., 0., 1.].into();
+        assert_eq!(p.budan_fourier_count(-1., 1.), 2);
+        assert_eq!(p.count_roots_in(-1., 1.), Ok(0));
+
+        // Multiple roots count once in Sturm's theorem, and with multiplicity in Budan–Fourier's.
+        let p = Polynomial::from_roots(&[(1., 2), (3., 1)]);
+        assert_eq!(p.count_roots_in(0., 4.), Ok(2));
+        assert_eq!(p.budan_fourier_count(0., 4.), 3);
     }
 
     #[test]
-    fn test_primitive() {
-        let a: Polynomial = [2., -4., -4.].into();
-        assert_eq!(a.primitive(), ([-1., 2., 2.].into(), -2.));
+    fn test_count_roots_in_unbounded() {
+        let (inf, neg_inf) = (f64::INFINITY, f64::NEG_INFINITY);
+        let p: Polynomial = [1., -3., 0., 1.].into();
+        assert_eq!(p.count_roots_in(neg_inf, inf), Ok(3));
+        assert_eq!(p.count_roots_in(neg_inf, 0.), Ok(1));
+        assert_eq!(p.count_roots_in(0., inf), Ok(2));
+        assert_eq!(p.count_roots_in(inf, inf), Ok(0));
+
+        // Even degree, negative leading coefficient, and a double root.
+        let p = -Polynomial::from_roots(&[(-1., 2), (2., 1), (5., 1)]);
+        assert_eq!(p.count_roots_in(neg_inf, inf), Ok(3));
+        assert_eq!(p.count_roots_in(neg_inf, 0.), Ok(1));
+        assert_eq!(p.count_roots_in(neg_inf, -1.5), Ok(0));
+        assert_eq!(p.count_roots_in(3., inf), Ok(1));
+        assert_eq!(
+            Polynomial::from([1., 0., 1.]).count_roots_in(neg_inf, inf),
+            Ok(0)
+        );
+        assert_eq!(Polynomial::from([2.]).count_roots_in(neg_inf, inf), Ok(0));
+
+        assert_eq!(p.count_roots_in(f64::NAN, 1.), Err(Error::NanBound));
+        assert_eq!(p.count_roots_in(0., f64::NAN), Err(Error::NanBound));
     }
 
-    use rand::Rng;
+    #[test]
+    fn test_even_odd() {
+        let even: Polynomial = [-8., 0., 14., 0., -7., 0., 1.].into();
+        assert!(even.is_even() && !even.is_odd());
+
+        let odd: Polynomial = [0., -1., 0., 1.].into();
+        assert!(odd.is_odd() && !odd.is_even());
+
+        let neither: Polynomial = [1., 1.].into();
+        assert!(!neither.is_even() && !neither.is_odd());
+        assert!(Polynomial::from([3.]).is_even());
+    }
+
+    #[test]
+    fn test_end_behavior() {
+        use Ordering::*;
+
+        let cases = [
+            (vec![1., 0., 2.], 1, (Greater, Greater)),
+            (vec![1., 0., -2.], -1, (Less, Less)),
+            (vec![1., 0., 0., 3.], 1, (Less, Greater)),
+            (vec![1., 0., 0., -3.], -1, (Greater, Less)),
+            (vec![-5.], -1, (Less, Less)),
+            (vec![], 0, (Equal, Equal)),
+        ];
+
+        for (coefs, sign, behavior) in cases {
+            let p: Polynomial = coefs.into();
+            assert_eq!(p.lead_sign(), sign);
+            assert_eq!(p.end_behavior(), behavior);
+        }
+    }
+
+    #[test]
+    fn test_truncate_pad() {
+        let p: Polynomial = [1., 0., 0., 2., 3.].into();
+        assert_eq!(p.truncate_to_grade(3), [1., 0., 0., 2.].into());
+        assert_eq!(p.truncate_to_grade(2), [1.].into());
+        assert_eq!(p.truncate_to_grade(2).grade(), 0);
+        assert_eq!(p.truncate_to_grade(10), p);
+        assert_eq!(p.truncate_to_grade(-1), Polynomial::ZERO);
+        assert_eq!(p.truncate_to_grade(i32::MAX), p);
+        assert_eq!(p.truncate_to_grade(i32::MIN), Polynomial::ZERO);
+
+        assert_eq!(p.pad_to_grade(6), [1., 0., 0., 2., 3., 0., 0.]);
+        assert_eq!(p.pad_to_grade(2), [1., 0., 0., 2., 3.]);
+        assert_eq!(Polynomial::ZERO.pad_to_grade(1), [0., 0.]);
+    }
+
+    #[test]
+    fn test_eval_derivatives() {
+        let p: Polynomial = [-6., 11., -6., 1.].into();
+        for x in [-2., 0., 0.5, 3., 10.] {
+            let mut out = [0.; 5];
+            p.eval_derivatives(x, &mut out);
+            for (k, &v) in out.iter().enumerate() {
+                assert_eq!(v, p.derivative_n(k as u32).evaluate(x), "p^({k})({x})");
+            }
+
+            let mut value = [1.];
+            p.eval_derivatives(x, &mut value);
+            assert_eq!(value[0], p.eval_compensated(x));
+        }
+
+        let mut out = [1.; 3];
+        Polynomial::ZERO.eval_derivatives(2., &mut out);
+        assert_eq!(out, [0.; 3]);
+        Polynomial::ZERO.eval_derivatives(2., &mut []);
+    }
+
+    #[test]
+    fn test_eval_with_derivative() {
+        let p = Polynomial::from_roots(&[(1., 1), (-2., 2), (0.5, 1)]);
+        let derivative = p.derivative();
+
+        for x in [-3., -2., 0., 0.25, 1., 7.5] {
+            let (v, d) = p.eval_with_derivative(x);
+            assert_eq!(v, p.eval_compensated(x));
+            assert!((v - p(x)).abs() <= 1e-12 * p(x).abs().max(1.));
+            assert!((d - derivative(x)).abs() <= 1e-12 * derivative(x).abs().max(1.));
+        }
+
+        assert_eq!(Polynomial::ZERO.eval_with_derivative(1.), (0., 0.));
+        assert_eq!(Polynomial::from([4.]).eval_with_derivative(1.), (4., 0.));
+    }
+
+    #[test]
+    fn test_map_to_monic_integer() {
+        let p: Polynomial = [0.5, -1.5, 1.].into();
+        assert_eq!(p.map_to_monic_integer(), ([1., -3., 2.].into(), 2.));
+
+        let p: Polynomial = [0.375, 0.25, -2.].into();
+        assert_eq!(p.map_to_monic_integer(), ([3., 2., -16.].into(), 8.));
+
+        // 1/3 isn't exact in binary, so its denominator is a large power of two.
+        let (_, factor) = Polynomial::from([1. / 3., 1.]).map_to_monic_integer();
+        assert_eq!(factor, 2f64.powi(54));
+
+        let p: Polynomial = [2., 4.].into();
+        assert_eq!(p.map_to_monic_integer(), (p.clone(), 1.));
+        assert_eq!(
+            Polynomial::ZERO.map_to_monic_integer(),
+            (Polynomial::ZERO, 1.)
+        );
+    }
+
+    #[test]
+    fn test_discriminant() {
+        let a: Polynomial = [-4., 0., 1.].into();
+        assert_eq!(a.discriminant(), 16.);
+
+        let a: Polynomial = [1., -2., 1.].into();
+        assert_eq!(a.discriminant(), 0.);
+
+        let a: Polynomial = [1., -3., 0., 1.].into(); // -4p^3 - 27q^2 = 108 - 27
+        assert_eq!(a.discriminant(), 81.);
+
+        assert_eq!(Polynomial::from([3., -2.]).discriminant(), 1.);
+        assert_eq!(Polynomial::from([3.]).discriminant(), 0.);
+        assert_eq!(Polynomial::ZERO.discriminant(), 0.);
+    }
+
+    #[bench]
+    fn bench_evaluate(b: &mut test::Bencher) {
+        let p = Polynomial(
+            rand::thread_rng()
+                .sample_iter(rand::distributions::Uniform::from(-1.0..1.0))
+                .take(1000)
+                .collect(),
+        );
+
+        b.iter(|| p(test::black_box(0.99)))
+    }
 
     #[bench]
     fn bench_to_rational(b: &mut test::Bencher) {
@@ -419,10 +2843,10 @@ mod tests {
     #[bench]
     fn bench_from_rational(b: &mut test::Bencher) {
         let mut rng = rand::thread_rng();
-        let r: Vec<_> = std::iter::from_fn(|| {
-            Some(Rational32::new_raw(
-                rng.gen_range(-1000..1000),
-                rng.gen_range(1..1000),
+        let r: Vec<_> = core::iter::from_fn(|| {
+            Some(BigRational::new_raw(
+                rng.gen_range(-1000..1000).into(),
+                rng.gen_range(1..1000).into(),
             ))
         })
         .take(1000)
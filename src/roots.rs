@@ -1,104 +1,384 @@
-use crate::float::Float;
+use crate::error::Error;
+use crate::float::{Float, TOLERANCE};
 use crate::polynomial::Polynomial;
-use std::cmp::Ordering;
+use alloc::{format, string::String, vec, vec::Vec};
+use core::{cmp::Ordering, f64::consts::PI};
+use num_complex::Complex64;
+#[cfg(not(any(feature = "std", test)))]
+use num_traits::Float as _;
 
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Root {
     pub value: f64,
     pub multiplicity: i32,
 }
 
+impl Root {
+    pub fn total_cmp(&self, other: &Self) -> Ordering {
+        self.value
+            .total_cmp(&other.value)
+            .then(self.multiplicity.cmp(&other.multiplicity))
+    }
+}
+
+pub fn sort_roots(roots: &mut [Root]) {
+    roots.sort_by(Root::total_cmp);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ComplexRoot {
+    pub value: Complex64,
+    pub multiplicity: i32,
+}
+
+#[derive(Debug, Default)]
+pub struct Trace(Option<Vec<String>>);
+
+impl Trace {
+    pub fn enabled() -> Self {
+        Self(Some(vec![]))
+    }
+
+    pub fn steps(&self) -> &[String] {
+        self.0.as_deref().unwrap_or_default()
+    }
+
+    fn step(&mut self, msg: impl FnOnce() -> String) {
+        if let Some(steps) = &mut self.0 {
+            steps.push(msg());
+        }
+    }
+}
+
+/// A snapshot of the approximate solver, passed to the progress callback after every iteration.
+#[derive(Debug, Clone, Copy)]
+pub struct IterationState<'a> {
+    pub iteration: usize,
+    pub estimates: &'a [Complex64],
+    pub residuals: &'a [f64],
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Polishing {
+    #[default]
+    Newton,
+    Halley,
+    /// Householder's method of the given order: 1 is Newton's method, 2 is Halley's. Order `d`
+    /// converges with order `d + 1` but evaluates `d` derivatives per step.
+    Householder(u32),
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Strategy {
+    /// Approximates all roots at once with Durand–Kerner iteration.
+    #[default]
+    Simultaneous,
+    /// Isolates the real roots with a Sturm sequence and finds them one at a time, deflating
+    /// each out of the polynomial. Complex roots are not computed.
+    Deflation,
+    /// Finds the roots one at a time with the three-stage Jenkins–Traub iteration, deflating
+    /// each out of the polynomial.
+    JenkinsTraub,
+}
+
+#[derive(Debug, Clone)]
+pub struct RootFinder {
+    pub max_iterations: usize,
+    pub strategy: Strategy,
+    pub polishing: Polishing,
+    /// Relative distance under which approximated roots are merged into one multiple root.
+    /// Too large a value merges close distinct roots, too small a value splits a multiple root,
+    /// whose approximations spread out by about `ε^(1/m)`, into several simple ones.
+    pub multiplicity_tolerance: f64,
+    /// Relative tolerance under which values count as equal or as zero: the two substitution
+    /// constants of a quartic quasi-palindrome, the middle terms of a binomial, and, through
+    /// `tolerance^(1/4)`, the imaginary parts of approximated real roots. Looser values suit
+    /// noisy coefficients.
+    pub tolerance: f64,
+    /// Picks the starting points of the Durand–Kerner iteration. Every seed, and no seed, gives
+    /// the same results on every run; a different seed can help when the iteration stalls.
+    pub seed: Option<u64>,
+}
+
+impl Default for RootFinder {
+    fn default() -> Self {
+        Self {
+            max_iterations: 1000,
+            strategy: Strategy::default(),
+            polishing: Polishing::default(),
+            multiplicity_tolerance: MULTIPLICITY_TOLERANCE,
+            tolerance: TOLERANCE,
+            seed: None,
+        }
+    }
+}
+
+pub struct Solution {
+    pub roots: Vec<Root>,
+    pub did_converge: bool,
+}
+
+impl RootFinder {
+    pub fn with_seed(self, seed: u64) -> Self {
+        Self {
+            seed: Some(seed),
+            ..self
+        }
+    }
+
+    pub fn solve(&self, p: &Polynomial) -> Option<Solution> {
+        self.solve_traced(p, &mut Trace::default())
+    }
+
+    pub fn solve_traced(&self, p: &Polynomial, trace: &mut Trace) -> Option<Solution> {
+        self.run(p, trace, None)
+    }
+
+    pub fn solve_with_progress(
+        &self,
+        p: &Polynomial,
+        mut progress: impl FnMut(&IterationState),
+    ) -> Option<Solution> {
+        self.run(p, &mut Trace::default(), Some(&mut progress))
+    }
+
+    fn run(
+        &self,
+        p: &Polynomial,
+        trace: &mut Trace,
+        progress: Option<&mut dyn FnMut(&IterationState)>,
+    ) -> Option<Solution> {
+        let mut ctx = Context {
+            finder: self,
+            trace,
+            progress,
+            did_converge: true,
+        };
+
+        let roots = solve(p, &mut ctx)?;
+
+        Some(Solution {
+            roots,
+            did_converge: ctx.did_converge,
+        })
+    }
+
+    /// Finds the non-real roots of `p`, each conjugate pair listed as `a+bi` followed by `a-bi`.
+    pub fn solve_complex(&self, p: &Polynomial) -> Option<Vec<ComplexRoot>> {
+        if p.grade() < 2 {
+            return (p.grade() >= 0).then(Vec::new);
+        }
+
+        let (iterates, _) =
+            durand_kerner(&p.monic(), self.max_iterations, self.initial_phase(), None);
+        let derivative = p.derivative();
+        let mut upper: Vec<_> = cluster_roots(&iterates, self.multiplicity_tolerance)
+            .into_iter()
+            .filter(|(z, _)| !is_real(*z, self.tolerance) && z.im > 0.)
+            .map(|(z, multiplicity)| {
+                let mut z = match multiplicity {
+                    1 => polish_complex_root(p, &derivative, z),
+                    _ => z,
+                };
+                if z.re.abs() <= self.tolerance.sqrt() * z.norm() {
+                    z.re = 0.;
+                }
+                (z, multiplicity)
+            })
+            .collect();
+        upper.sort_by(|(a, _), (b, _)| a.re.total_cmp(&b.re).then(a.im.total_cmp(&b.im)));
+
+        Some(
+            upper
+                .into_iter()
+                .flat_map(|(z, multiplicity)| {
+                    [z, z.conj()].map(|value| ComplexRoot {
+                        value,
+                        multiplicity,
+                    })
+                })
+                .collect(),
+        )
+    }
+
+    /// The angle of the first starting point of the Durand–Kerner iteration.
+    fn initial_phase(&self) -> f64 {
+        let Some(seed) = self.seed else {
+            return 0.4;
+        };
+
+        // SplitMix64, so that consecutive seeds are spread over the whole circle.
+        let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+        2. * PI * (z >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+struct Context<'a, 'p> {
+    finder: &'a RootFinder,
+    trace: &'a mut Trace,
+    progress: Option<&'a mut (dyn FnMut(&IterationState) + 'p)>,
+    did_converge: bool,
+}
+
 pub fn find_roots(p: &Polynomial) -> Option<Vec<Root>> {
+    debug_assert!(
+        p.iter().all(|(_, v)| v.is_finite()),
+        "{p:?} has non-finite coefficients"
+    );
+    RootFinder::default().solve(p).map(|s| s.roots)
+}
+
+/// Like `find_roots`, but reports non-finite coefficients, which arithmetic on very large
+/// coefficients can produce, instead of returning meaningless roots.
+pub fn find_roots_checked(p: &Polynomial) -> Result<Option<Vec<Root>>, Error> {
+    match p.iter().find(|(_, v)| !v.is_finite()) {
+        Some((index, _)) => Err(Error::NonFinite {
+            index: index as usize,
+        }),
+        None => Ok(find_roots(p)),
+    }
+}
+
+pub fn find_complex_roots(p: &Polynomial) -> Option<Vec<ComplexRoot>> {
+    RootFinder::default().solve_complex(p)
+}
+
+fn solve(p: &Polynomial, ctx: &mut Context) -> Option<Vec<Root>> {
     match p.grade() {
-        -1 => None,
-        0 => Some(vec![]),
-        1 => Some(get_roots_order_one(p)),
-        2 => Some(get_roots_order_two(p)),
-        _ => Some(get_roots_general(p)),
+        -1 => {
+            ctx.trace
+                .step(|| "zero polynomial: every value is a root".into());
+            None
+        }
+        0 => {
+            ctx.trace.step(|| format!("nonzero constant {p}: no roots"));
+            Some(vec![])
+        }
+        1 => Some(get_roots_order_one(p, ctx)),
+        2 => Some(get_roots_order_two(p, ctx)),
+        _ => Some(get_roots_general(p, ctx)),
     }
 }
 
-fn get_roots_order_one(p: &Polynomial) -> Vec<Root> {
+fn get_roots_order_one(p: &Polynomial, ctx: &mut Context) -> Vec<Root> {
+    ctx.trace
+        .step(|| format!("linear {p}: x = -({}) / {}", p[0], p[1]));
+
     vec![Root {
         value: p[0].negate() / p[1],
         multiplicity: 1,
     }]
 }
 
-fn get_roots_order_two(p: &Polynomial) -> Vec<Root> {
+fn get_roots_order_two(p: &Polynomial, ctx: &mut Context) -> Vec<Root> {
     let two_a = 2. * p[2];
     let delta = p[1] * p[1] - 2. * two_a * p[0];
 
+    ctx.trace.step(|| format!("quadratic {p}: delta = {delta}"));
+
     delta.partial_cmp(&0.).map_or(vec![], |o| match o {
         Ordering::Less => vec![],
         Ordering::Equal => vec![Root {
             value: -p[1] / two_a,
             multiplicity: 2,
         }],
-        Ordering::Greater => vec![
-            Root {
-                value: (-p[1] - delta.sqrt()) / two_a,
-                multiplicity: 1,
-            },
-            Root {
-                value: (-p[1] + delta.sqrt()) / two_a,
-                multiplicity: 1,
-            },
-        ],
+        Ordering::Greater => {
+            // Adding √Δ with the sign of b never cancels; the other root follows from x1·x2 = c/a.
+            let q = -(p[1] + delta.sqrt().copysign(p[1])) / 2.;
+            let (x1, x2) = (q / p[2], p[0] / q);
+
+            [x1.min(x2), x1.max(x2)]
+                .map(|value| Root {
+                    value,
+                    multiplicity: 1,
+                })
+                .into()
+        }
     })
 }
 
-fn get_roots_general(p: &Polynomial) -> Vec<Root> {
-    get_roots_biquadratic(p)
-        .or_else(|| get_roots_binomial(p))
-        .or_else(|| get_roots_palindrome(p))
-        .unwrap_or_else(|| approximate_roots(p))
+fn get_roots_general(p: &Polynomial, ctx: &mut Context) -> Vec<Root> {
+    get_roots_zero(p, ctx)
+        .or_else(|| get_roots_binomial(p, ctx))
+        .or_else(|| get_roots_even(p, ctx))
+        .or_else(|| get_roots_palindrome(p, ctx))
+        .or_else(|| get_roots_rational(p, ctx))
+        .or_else(|| get_roots_repeated(p, ctx))
+        .or_else(|| get_roots_reciprocal(p, ctx))
+        .unwrap_or_else(|| approximate_roots(p, ctx))
 }
 
-fn get_roots_binomial(p: &Polynomial) -> Option<Vec<Root>> {
-    use std::f64::consts::PI;
-
+fn get_roots_binomial(p: &Polynomial, ctx: &mut Context) -> Option<Vec<Root>> {
     let grade = p.grade();
-    if (1..grade).any(|i| p[i] != 0.) {
+    // Terms that are negligible at the magnitude of the roots count as noise in the coefficients.
+    let r = (p[0] / p[grade]).abs().powf(1. / grade as f64);
+    let tol = ctx.finder.tolerance;
+    if (1..grade).any(|i| !(p[i] * r.powi(i)).near_zero_rel_within(p[0], tol)) {
         return None;
     }
 
-    let first = p[0];
-    let last = p[grade];
-    let abs = (-first / last).abs().powf(1. / (grade as f64));
-    let init_phi = (-first.signum()).acos();
+    let t = -p[0] / p[grade];
+    let abs = t.abs().powf(1. / (grade as f64));
 
-    let root_values = (0..grade)
-        .flat_map(|k| {
-            let phi = (init_phi + PI * (2 * k) as f64) / grade as f64;
+    ctx.trace
+        .step(|| format!("binomial {p}: real roots of x^{grade} = {t}"));
 
-            phi.sin().abs().near_zero().then(|| abs * phi.cos())
-        })
-        .map(|value| Root {
-            value,
-            multiplicity: 1,
-        })
-        .collect::<Vec<_>>();
+    // The roots are `abs` times the n-th roots of unity, turned by π/n when t < 0, so the only
+    // real ones are those at phase 0 or π.
+    let root_values = match (grade % 2, t > 0.) {
+        (1, _) => vec![abs.copysign(t)],
+        (_, true) => vec![-abs, abs],
+        (_, false) => vec![],
+    };
 
-    Some(root_values)
+    Some(
+        root_values
+            .into_iter()
+            .map(|value| Root {
+                value,
+                multiplicity: 1,
+            })
+            .collect(),
+    )
 }
 
-fn get_roots_biquadratic(p: &Polynomial) -> Option<Vec<Root>> {
-    if (p.grade(), p[1], p[3]) != (4, 0., 0.) {
+fn get_roots_even(p: &Polynomial, ctx: &mut Context) -> Option<Vec<Root>> {
+    if !p.is_even() {
         return None;
     }
 
-    let roots = get_roots_order_two(&[p[0], p[2], p[4]].into())
+    let q: Polynomial = p
+        .iter()
+        .step_by(2)
+        .map(|(_, v)| v)
+        .collect::<Vec<_>>()
+        .into();
+    ctx.trace.step(|| {
+        let kind = if p.grade() == 4 {
+            "biquadratic"
+        } else {
+            "even polynomial"
+        };
+        format!("{kind} {p}: substituting y = x^2 gives {q}")
+    });
+
+    let roots = solve(&q, ctx)?
         .into_iter()
-        .filter(|r| r.value < 0.)
+        .filter(|r| r.value >= 0.)
         .flat_map(|r| {
             let sqrt = r.value.sqrt();
+            let is_zero = r.value == 0.;
 
+            // x^2 = 0 has 0 as a double root, while x^2 = y for y > 0 has two simple roots.
             [-sqrt, sqrt]
                 .into_iter()
-                .skip((r.value == 0.) as usize)
+                .skip(is_zero as usize)
                 .map(move |value| Root {
                     value,
-                    multiplicity: r.multiplicity,
+                    multiplicity: r.multiplicity * if is_zero { 2 } else { 1 },
                 })
         })
         .collect();
@@ -106,10 +386,142 @@ fn get_roots_biquadratic(p: &Polynomial) -> Option<Vec<Root>> {
     Some(roots)
 }
 
-fn get_roots_palindrome(p: &Polynomial) -> Option<Vec<Root>> {
+fn get_roots_rational(p: &Polynomial, ctx: &mut Context) -> Option<Vec<Root>> {
+    let (rational, rest) = p.rational_roots()?;
+
+    ctx.trace.step(|| {
+        let roots: Vec<_> = rational.iter().map(|(r, _)| format!("{r}")).collect();
+        format!(
+            "{p}: has rational roots {}, dividing them out leaves {rest}",
+            roots.join(", ")
+        )
+    });
+
+    let mut roots: Vec<_> = rational
+        .into_iter()
+        .map(|(value, multiplicity)| Root {
+            value,
+            multiplicity,
+        })
+        .collect();
+    roots.extend(solve(&rest, ctx)?);
+
+    Some(roots)
+}
+
+fn get_roots_repeated(p: &Polynomial, ctx: &mut Context) -> Option<Vec<Root>> {
+    if p.is_squarefree_modulo_prime() {
+        return None;
+    }
+
+    let factors = p.squarefree_factorization();
+    if let [(_, 1)] = factors[..] {
+        return None;
+    }
+
+    ctx.trace.step(|| {
+        let factors: Vec<_> = factors
+            .iter()
+            .map(|(f, m)| match m {
+                1 => format!("({f})"),
+                _ => format!("({f})^{m}"),
+            })
+            .collect();
+        format!(
+            "{p}: has repeated roots, factoring into {}",
+            factors.join(" * ")
+        )
+    });
+
+    // Each factor is squarefree, so its roots are distinct and the multiplicities are exact.
+    let mut roots = vec![];
+    for (factor, multiplicity) in &factors {
+        roots.extend(solve(factor, ctx)?.into_iter().map(|r| Root {
+            value: r.value,
+            multiplicity: r.multiplicity * multiplicity,
+        }));
+    }
+
+    Some(roots)
+}
+
+const LARGE_ROOT_BOUND: f64 = 1e4;
+
+// Only taken when every root is outside the unit circle, so that none of them becomes large in
+// turn. The reciprocal is scaled by the root bound `b`, as the tolerances are absolute below 1,
+// and its roots `b/x` are inverted and polished on `p` itself.
+fn get_roots_reciprocal(p: &Polynomial, ctx: &mut Context) -> Option<Vec<Root>> {
+    let b = p.root_bound()?;
+    if b <= LARGE_ROOT_BOUND || p.reciprocal().root_bound()? >= 1. {
+        return None;
+    }
+
+    let q = scaled_reciprocal(p, b)?;
+    ctx.trace
+        .step(|| format!("{p}: the roots are large, solving the reciprocal scaled by {b}: {q}"));
+
+    Some(invert_roots(p, b, solve(&q, ctx)?))
+}
+
+pub(crate) fn reciprocal_roots(p: &Polynomial) -> Option<Vec<Root>> {
+    if p[0] == 0. {
+        return None;
+    }
+
+    let b = p.root_bound().unwrap_or(1.);
+    let mut roots = invert_roots(p, b, find_roots(&scaled_reciprocal(p, b)?)?);
+    sort_roots(&mut roots);
+    Some(roots)
+}
+
+// The reciprocal with its roots multiplied by `b`, or `None` if its coefficients overflow.
+fn scaled_reciprocal(p: &Polynomial, b: f64) -> Option<Polynomial> {
+    let r = p.reciprocal();
+    let n = r.grade();
+    Polynomial::try_from_coefficients(r.iter().map(|(i, v)| v * b.powi(n - i)).collect::<Vec<_>>())
+        .ok()
+}
+
+fn invert_roots(p: &Polynomial, b: f64, roots: Vec<Root>) -> Vec<Root> {
+    roots
+        .into_iter()
+        .map(|r| Root {
+            value: match r.multiplicity {
+                1 => polish_root(p, b / r.value),
+                _ => b / r.value,
+            },
+            multiplicity: r.multiplicity,
+        })
+        .collect()
+}
+
+fn get_roots_zero(p: &Polynomial, ctx: &mut Context) -> Option<Vec<Root>> {
+    let k = p.iter().take_while(|&(_, v)| v == 0.).count();
+    if k == 0 {
+        return None;
+    }
+
+    let q: Polynomial = p.iter().skip(k).map(|(_, v)| v).collect::<Vec<_>>().into();
+    ctx.trace
+        .step(|| format!("{p}: 0 is a root of multiplicity {k}, dividing by x^{k} gives {q}"));
+
+    let mut roots = solve(&q, ctx)?;
+    roots.push(Root {
+        value: 0.,
+        multiplicity: k as i32,
+    });
+
+    Some(roots)
+}
+
+fn get_roots_palindrome(p: &Polynomial, ctx: &mut Context) -> Option<Vec<Root>> {
     return match p.grade() {
         g if g % 2 == 1 && p.is_palindrome() => {
-            let mut roots = find_roots(&p.clone().div_rem(&[1., 1.].into()).0)?;
+            let q = p.clone().div_rem(&[1., 1.].into()).0;
+            ctx.trace
+                .step(|| format!("odd palindrome {p}: -1 is a root, dividing by x+1 gives {q}"));
+
+            let mut roots = solve(&q, ctx)?;
             if let Some(root) = roots.iter_mut().find(|r| r.value == -1.) {
                 root.multiplicity += 1
             } else {
@@ -121,22 +533,57 @@ fn get_roots_palindrome(p: &Polynomial) -> Option<Vec<Root>> {
 
             Some(roots)
         }
-        4 => get_roots_quartic_quasi_palindrome(p),
+        4 => get_roots_quartic_quasi_palindrome(p, ctx),
+        g if g % 2 == 0 && p.is_palindrome() => get_roots_even_palindrome(p, ctx),
         _ => None,
     };
 
-    fn get_roots_quartic_quasi_palindrome(p: &Polynomial) -> Option<Vec<Root>> {
+    // x^-k·p(x) = a_k + Σ a_(k+j)·(x^j + x^-j), and x^j + x^-j is a polynomial in y = x + 1/x.
+    fn get_roots_even_palindrome(p: &Polynomial, ctx: &mut Context) -> Option<Vec<Root>> {
+        let k = p.grade() / 2;
+        let y = Polynomial::from([0., 1.]);
+        let (mut prev, mut curr) = (Polynomial::from([2.]), y.clone());
+        let mut q = Polynomial::from([p[k]]);
+
+        for j in 1..=k {
+            q = q + curr.map_coefficients(|v| v * p[k + j]);
+            (prev, curr) = (curr.clone(), &y * &curr - prev);
+        }
+
+        ctx.trace
+            .step(|| format!("even palindrome {p}: substituting y = x + 1/x gives {q}"));
+
+        let roots = solve(&q, ctx)?
+            .into_iter()
+            .flat_map(|r| {
+                get_roots_order_two(&[1., -r.value, 1.].into(), ctx)
+                    .into_iter()
+                    .map(move |mut qr| {
+                        qr.multiplicity *= r.multiplicity;
+                        qr
+                    })
+            })
+            .collect();
+
+        Some(roots)
+    }
+
+    fn get_roots_quartic_quasi_palindrome(p: &Polynomial, ctx: &mut Context) -> Option<Vec<Root>> {
         let m = (p[0] / p[4]).sqrt();
         let m2 = p[1] / p[3];
 
-        if m != m2 {
+        if m.is_nan() || (m - m2).abs() > ctx.finder.tolerance * m {
             return None;
         }
 
-        let roots = get_roots_order_two(&[p[2] - 2. * p[4] * m, p[3], p[4]].into())
+        let q: Polynomial = [p[2] - 2. * p[4] * m, p[3], p[4]].into();
+        ctx.trace
+            .step(|| format!("quartic quasi-palindrome {p}: substituting y = x + {m}/x gives {q}"));
+
+        let roots = get_roots_order_two(&q, ctx)
             .into_iter()
             .flat_map(|r| {
-                get_roots_order_two(&[m, -r.value, 1.].into())
+                get_roots_order_two(&[m, -r.value, 1.].into(), ctx)
                     .into_iter()
                     .map(move |mut qr| {
                         qr.multiplicity *= r.multiplicity;
@@ -149,6 +596,1700 @@ fn get_roots_palindrome(p: &Polynomial) -> Option<Vec<Root>> {
     }
 }
 
-fn approximate_roots(_p: &Polynomial) -> Vec<Root> {
-    todo!("roots approximation algorithm");
+const MAX_POLISH_ITERATIONS: usize = 50;
+const GRAEFFE_ITERATIONS: u32 = 4;
+const MULTIPLICITY_TOLERANCE: f64 = 1e-4;
+const NO_SHIFT_ITERATIONS: usize = 5;
+const FIXED_SHIFTS: usize = 9;
+const VARIABLE_SHIFT_ITERATIONS: usize = 10;
+
+fn approximate_roots(p: &Polynomial, ctx: &mut Context) -> Vec<Root> {
+    ctx.trace
+        .step(|| format!("{p}: no closed form applies, approximating the roots"));
+
+    if ctx.finder.strategy == Strategy::Deflation {
+        return deflation_roots(p);
+    }
+
+    let (iterates, did_converge) = match ctx.finder.strategy {
+        Strategy::JenkinsTraub => jenkins_traub(&p.monic()),
+        _ => durand_kerner(
+            &p.monic(),
+            ctx.finder.max_iterations,
+            ctx.finder.initial_phase(),
+            ctx.progress.as_mut().map(|f| &mut **f as _),
+        ),
+    };
+    if !did_converge {
+        ctx.did_converge = false;
+        ctx.trace.step(|| {
+            format!(
+                "stopped after {} iterations without converging",
+                ctx.finder.max_iterations
+            )
+        });
+    }
+
+    cluster_roots(&iterates, ctx.finder.multiplicity_tolerance)
+        .into_iter()
+        .filter(|(z, _)| is_real(*z, ctx.finder.tolerance))
+        // The real part of a complex root with a small imaginary part is no root itself, though
+        // polishing can carry it to a neighbouring real one.
+        .filter(|&(z, m)| m > 1 || p.is_root_within(z.re, ctx.finder.tolerance.sqrt()))
+        .map(|(z, multiplicity)| Root {
+            value: match multiplicity {
+                1 => match ctx.finder.polishing {
+                    Polishing::Newton => polish_root(p, z.re),
+                    Polishing::Halley => polish_root_halley(p, z.re),
+                    Polishing::Householder(order) => polish_root_householder(p, order, z.re),
+                },
+                _ => z.re,
+            },
+            multiplicity,
+        })
+        .collect()
+}
+
+const MAX_QR_ITERATIONS: usize = 30;
+
+pub(crate) fn companion_roots(p: &Polynomial) -> Option<Vec<ComplexRoot>> {
+    let mut a = companion_matrix(p)?;
+    balance(&mut a);
+
+    let mut roots: Vec<_> = cluster_roots(&hessenberg_eigenvalues(a)?, MULTIPLICITY_TOLERANCE)
+        .into_iter()
+        .map(|(mut value, multiplicity)| {
+            if is_real(value, TOLERANCE) {
+                value.im = 0.;
+            }
+            ComplexRoot {
+                value,
+                multiplicity,
+            }
+        })
+        .collect();
+    roots.sort_by(|a, b| {
+        (a.value.re)
+            .total_cmp(&b.value.re)
+            .then(a.value.im.total_cmp(&b.value.im))
+    });
+
+    Some(roots)
+}
+
+pub(crate) fn balanced_companion(p: &Polynomial) -> Option<Vec<Vec<f64>>> {
+    let mut a = companion_matrix(p)?;
+    balance(&mut a);
+
+    Some(a.into_iter().skip(1).map(|row| row[1..].to_vec()).collect())
+}
+
+fn companion_matrix(p: &Polynomial) -> Option<Vec<Vec<f64>>> {
+    let n = p.grade();
+    if n < 0 {
+        return None;
+    }
+
+    let n = n as usize;
+    // The matrices are indexed from 1, leaving row and column 0 unused, to keep the
+    // QR iteration readable.
+    let mut a = vec![vec![0.; n + 1]; n + 1];
+    for k in 1..=n {
+        a[1][k] = -p[(n - k) as i32] / p.lead();
+    }
+    for j in 2..=n {
+        a[j][j - 1] = 1.;
+    }
+
+    Some(a)
+}
+
+// Scales rows and columns by powers of two so that their norms are comparable, which
+// reduces the rounding error of the eigenvalues without changing them.
+fn balance(a: &mut [Vec<f64>]) {
+    const RADIX: f64 = 2.;
+    let n = a.len() - 1;
+    let mut done = false;
+
+    while !done {
+        done = true;
+
+        for i in 1..=n {
+            let (mut c, r) = (1..=n)
+                .filter(|&j| j != i)
+                .fold((0., 0.), |(c, r), j| (c + a[j][i].abs(), r + a[i][j].abs()));
+            if c == 0. || r == 0. {
+                continue;
+            }
+
+            let s = c + r;
+            let mut f = 1.;
+            while c < r / RADIX {
+                f *= RADIX;
+                c *= RADIX * RADIX;
+            }
+            while c > r * RADIX {
+                f /= RADIX;
+                c /= RADIX * RADIX;
+            }
+
+            if (c + r) / f < 0.95 * s {
+                done = false;
+                (1..=n).for_each(|j| a[i][j] /= f);
+                (1..=n).for_each(|j| a[j][i] *= f);
+            }
+        }
+    }
+}
+
+// Francis double shift QR iteration on an upper Hessenberg matrix.
+fn hessenberg_eigenvalues(mut a: Vec<Vec<f64>>) -> Option<Vec<Complex64>> {
+    let n = a.len() - 1;
+    let mut res = vec![Complex64::new(0., 0.); n + 1];
+
+    let norm: f64 = (1..=n)
+        .flat_map(|i| (i.max(2) - 1..=n).map(move |j| (i, j)))
+        .map(|(i, j)| a[i][j].abs())
+        .sum();
+
+    let mut nn = n;
+    let mut t = 0.;
+
+    while nn >= 1 {
+        let mut its = 0;
+
+        loop {
+            let mut l = nn;
+            while l >= 2 {
+                let mut s = a[l - 1][l - 1].abs() + a[l][l].abs();
+                if s == 0. {
+                    s = norm;
+                }
+                if a[l][l - 1].abs() + s == s {
+                    a[l][l - 1] = 0.;
+                    break;
+                }
+                l -= 1;
+            }
+
+            let mut x = a[nn][nn];
+            if l == nn {
+                res[nn] = Complex64::new(x + t, 0.);
+                nn -= 1;
+                break;
+            }
+
+            let mut y = a[nn - 1][nn - 1];
+            let mut w = a[nn][nn - 1] * a[nn - 1][nn];
+            if l == nn - 1 {
+                let p = 0.5 * (y - x);
+                let q = p * p + w;
+                let z = q.abs().sqrt();
+                x += t;
+
+                if q >= 0. {
+                    let z = p + z.copysign(p);
+                    res[nn - 1] = Complex64::new(x + z, 0.);
+                    res[nn] = Complex64::new(if z != 0. { x - w / z } else { x + z }, 0.);
+                } else {
+                    res[nn - 1] = Complex64::new(x + p, -z);
+                    res[nn] = Complex64::new(x + p, z);
+                }
+
+                nn -= 2;
+                break;
+            }
+
+            if its == MAX_QR_ITERATIONS {
+                return None;
+            }
+            if its == 10 || its == 20 {
+                // Exceptional shift, to break out of cycles.
+                t += x;
+                (1..=nn).for_each(|i| a[i][i] -= x);
+                let s = a[nn][nn - 1].abs() + a[nn - 1][nn - 2].abs();
+                x = 0.75 * s;
+                y = x;
+                w = -0.4375 * s * s;
+            }
+            its += 1;
+
+            let (mut p, mut q, mut r);
+            let mut m = nn - 2;
+            loop {
+                let z = a[m][m];
+                let (rr, ss) = (x - z, y - z);
+                p = (rr * ss - w) / a[m + 1][m] + a[m][m + 1];
+                q = a[m + 1][m + 1] - z - rr - ss;
+                r = a[m + 2][m + 1];
+                let s = p.abs() + q.abs() + r.abs();
+                p /= s;
+                q /= s;
+                r /= s;
+
+                if m == l {
+                    break;
+                }
+                let u = a[m][m - 1].abs() * (q.abs() + r.abs());
+                let v = p.abs() * (a[m - 1][m - 1].abs() + z.abs() + a[m + 1][m + 1].abs());
+                if u + v == v {
+                    break;
+                }
+                m -= 1;
+            }
+
+            for i in m + 2..=nn {
+                a[i][i - 2] = 0.;
+                if i != m + 2 {
+                    a[i][i - 3] = 0.;
+                }
+            }
+
+            for k in m..nn {
+                if k != m {
+                    p = a[k][k - 1];
+                    q = a[k + 1][k - 1];
+                    r = if k != nn - 1 { a[k + 2][k - 1] } else { 0. };
+                    x = p.abs() + q.abs() + r.abs();
+                    if x != 0. {
+                        p /= x;
+                        q /= x;
+                        r /= x;
+                    }
+                }
+
+                let s = (p * p + q * q + r * r).sqrt().copysign(p);
+                if s == 0. {
+                    continue;
+                }
+
+                if k == m {
+                    if l != m {
+                        a[k][k - 1] = -a[k][k - 1];
+                    }
+                } else {
+                    a[k][k - 1] = -s * x;
+                }
+
+                p += s;
+                x = p / s;
+                y = q / s;
+                let z = r / s;
+                q /= p;
+                r /= p;
+
+                for j in k..=nn {
+                    let mut p = a[k][j] + q * a[k + 1][j];
+                    if k != nn - 1 {
+                        p += r * a[k + 2][j];
+                        a[k + 2][j] -= p * z;
+                    }
+                    a[k + 1][j] -= p * y;
+                    a[k][j] -= p * x;
+                }
+
+                for row in &mut a[l..=nn.min(k + 3)] {
+                    let mut p = x * row[k] + y * row[k + 1];
+                    if k != nn - 1 {
+                        p += z * row[k + 2];
+                        row[k + 2] -= p * r;
+                    }
+                    row[k + 1] -= p * q;
+                    row[k] -= p;
+                }
+            }
+        }
+    }
+
+    res.remove(0);
+    Some(res)
+}
+
+// The approximations of an m-fold root spread out by about `tol^(1/m)`, so this keeps real roots
+// of multiplicity up to 4 from passing for complex ones.
+fn is_real(z: Complex64, tol: f64) -> bool {
+    z.im.abs() <= tol.powf(0.25) * z.norm().max(1.)
+}
+
+pub(crate) fn deflation_roots(p: &Polynomial) -> Vec<Root> {
+    let mut roots: Vec<_> = find_roots_iter(p).collect();
+    sort_roots(&mut roots);
+    roots
+}
+
+/// Yields the real roots smallest in magnitude first. The roots are isolated up front, but each
+/// is only bisected and polished once it is the next one to yield, so stopping early skips that
+/// work for the remaining roots.
+pub fn find_roots_iter(p: &Polynomial) -> impl Iterator<Item = Root> {
+    let mut factors = Vec::new();
+    // Isolating intervals `(a, b]` of the roots of each factor, and the bisected roots as `(x, x]`.
+    let mut pending = Vec::new();
+    for (factor, multiplicity) in p.squarefree_factorization() {
+        pending.extend(
+            factor
+                .isolate_roots()
+                .into_iter()
+                .map(|(a, b)| (factors.len(), a, b)),
+        );
+        factors.push((factor.clone(), factor, multiplicity));
+    }
+
+    // The smallest magnitude a root in `(a, b]` can have.
+    let magnitude = |&(_, a, b): &(usize, f64, f64)| {
+        if a < 0. && b >= 0. {
+            0.
+        } else {
+            a.abs().min(b.abs())
+        }
+    };
+
+    core::iter::from_fn(move || loop {
+        let (k, _) = pending
+            .iter()
+            .enumerate()
+            .min_by(|(_, x), (_, y)| magnitude(x).total_cmp(&magnitude(y)))?;
+        let (i, a, b) = pending.swap_remove(k);
+        let (factor, deflated, multiplicity) = &mut factors[i];
+
+        if a != b {
+            let x = bisect_root(factor, a, b, TOLERANCE.sqrt());
+            pending.push((i, x, x));
+            continue;
+        }
+
+        // Deflating the smallest roots first keeps the rounding errors introduced by deflation
+        // small.
+        let x = polish_root(deflated, a);
+        // Polishing against the undeflated factor removes the error accumulated by deflation.
+        let value = polish_root(factor, x);
+        *deflated = deflated.synthetic_deflate(value);
+        return Some(Root {
+            value,
+            multiplicity: *multiplicity,
+        });
+    })
+}
+
+/// Finds every real root by bisecting the sign change in each interval from `isolate_roots`.
+/// Slower than the closed forms and approximations, but it always converges and never cancels.
+pub fn find_roots_by_bisection(p: &Polynomial) -> Option<Vec<Root>> {
+    if p.grade() == -1 {
+        return None;
+    }
+
+    let mut roots: Vec<_> = p
+        .squarefree_factorization()
+        .into_iter()
+        .flat_map(|(factor, multiplicity)| {
+            factor.isolate_roots().into_iter().map(move |(a, b)| Root {
+                value: bisect_root(&factor, a, b, 0.),
+                multiplicity,
+            })
+        })
+        .collect();
+
+    sort_roots(&mut roots);
+    Some(roots)
+}
+
+/// Narrows a sign change of `p` in `(a, b]` until it is at most `tol` wide relative to its
+/// magnitude, or as narrow as f64 allows.
+pub(crate) fn bisect_root(p: &Polynomial, mut a: f64, mut b: f64, tol: f64) -> f64 {
+    let sign_b = p.eval_compensated(b).signum();
+    loop {
+        let mid = (a + b) / 2.;
+        if b - a <= tol * a.abs().max(b.abs()).max(1.) || mid <= a || mid >= b {
+            return mid;
+        }
+
+        let value = p.eval_compensated(mid);
+        if value == 0. {
+            return mid;
+        }
+        if value.signum() == sign_b {
+            b = mid;
+        } else {
+            a = mid;
+        }
+    }
+}
+
+fn polish_root(p: &Polynomial, x0: f64) -> f64 {
+    polish(p, x0, |x| newton_step(p, x)).0
+}
+
+fn newton_step(p: &Polynomial, x: f64) -> Option<f64> {
+    let mut c = [0.; 2];
+    p.eval_derivatives(x, &mut c);
+    let [v, d] = c;
+    (d != 0.).then(|| v / d)
+}
+
+// Householder's method of order d converges with order d + 1 on simple roots; order 1 is Newton's
+// and order 2 is Halley's.
+fn polish_root_householder(p: &Polynomial, order: u32, x0: f64) -> f64 {
+    let n = order.max(1) as usize + 1;
+    let (mut c, mut g) = (vec![0.; n], vec![0.; n]);
+    polish(p, x0, |x| householder_step(p, &mut c, &mut g, x)).0
+}
+
+/// The step `-d·g^(d-1)(x) / g^(d)(x)`, with `g = 1/p` and `d + 1` the length of the scratch
+/// buffers `c` and `g`. It is computed from the Taylor coefficients of `p` at `x`, inverting the
+/// series to get those of `g`.
+fn householder_step(p: &Polynomial, c: &mut [f64], g: &mut [f64], x: f64) -> Option<f64> {
+    p.eval_derivatives(x, c);
+    if c[0] == 0. {
+        return None;
+    }
+
+    let mut factorial = 1.;
+    for (k, v) in c.iter_mut().enumerate().skip(1) {
+        factorial *= k as f64;
+        *v /= factorial;
+    }
+
+    g[0] = 1. / c[0];
+    for k in 1..c.len() {
+        g[k] = -(1..=k).map(|j| c[j] * g[k - j]).sum::<f64>() / c[0];
+    }
+
+    let d = g.len() - 1;
+    (g[d] != 0.).then(|| -g[d - 1] / g[d])
+}
+
+// Halley's method converges cubically on simple roots, and faster than Newton's near
+// multiple ones, where the first derivative flattens out.
+fn polish_root_halley(p: &Polynomial, x0: f64) -> f64 {
+    polish(p, x0, |x| halley_step(p, x)).0
+}
+
+fn halley_step(p: &Polynomial, x: f64) -> Option<f64> {
+    let mut c = [0.; 3];
+    p.eval_derivatives(x, &mut c);
+    let [v, d, dd] = c;
+    let denom = 2. * d * d - v * dd;
+    (denom != 0.).then(|| 2. * v * d / denom)
+}
+
+fn polish(p: &Polynomial, x0: f64, mut step: impl FnMut(f64) -> Option<f64>) -> (f64, usize) {
+    let mut x = x0;
+    let mut iterations = 0;
+
+    while iterations < MAX_POLISH_ITERATIONS {
+        let Some(step) = step(x) else {
+            break;
+        };
+        x -= step;
+        iterations += 1;
+
+        if step.abs() <= TOLERANCE * x.abs().max(1.) {
+            break;
+        }
+    }
+
+    if p.eval_compensated(x).abs() <= p.eval_compensated(x0).abs() {
+        (x, iterations)
+    } else {
+        (x0, iterations)
+    }
+}
+
+fn polish_complex_root(p: &Polynomial, derivative: &Polynomial, z0: Complex64) -> Complex64 {
+    let mut z = z0;
+
+    for _ in 0..MAX_POLISH_ITERATIONS {
+        let d = eval_complex(derivative, z);
+        if d.norm() == 0. {
+            break;
+        }
+
+        let step = eval_complex(p, z) / d;
+        z -= step;
+
+        if step.norm() <= TOLERANCE * z.norm().max(1.) {
+            break;
+        }
+    }
+
+    if eval_complex(p, z).norm() <= eval_complex(p, z0).norm() {
+        z
+    } else {
+        z0
+    }
+}
+
+fn durand_kerner(
+    p: &Polynomial,
+    max_iterations: usize,
+    phase: f64,
+    mut progress: Option<&mut dyn FnMut(&IterationState)>,
+) -> (Vec<Complex64>, bool) {
+    let n = p.grade() as usize;
+    let mut magnitudes = p.graeffe_magnitudes(GRAEFFE_ITERATIONS);
+    if magnitudes.len() != n || !magnitudes.iter().all(|m| m.is_finite()) {
+        magnitudes = vec![p.root_bound().unwrap_or(1.); n];
+    }
+    let min_magnitude = f64::EPSILON * magnitudes[n - 1].max(1.);
+    let mut z: Vec<_> = magnitudes
+        .into_iter()
+        .enumerate()
+        .map(|(k, r)| {
+            Complex64::from_polar(r.max(min_magnitude), 2. * PI * k as f64 / n as f64 + phase)
+        })
+        .collect();
+
+    // An iterate has converged once its residual is within the rounding error of evaluating p.
+    let error_bound = |z: Complex64| {
+        let r = z.norm();
+        let bound = p.iter().rev().fold(0., |acc, (_, c)| acc * r + c.abs());
+        4. * n as f64 * f64::EPSILON * bound
+    };
+    let mut converged = vec![false; n];
+    let mut residuals = match progress {
+        Some(_) => vec![f64::INFINITY; n],
+        None => vec![],
+    };
+
+    for iteration in 0..max_iterations {
+        for k in 0..n {
+            if converged[k] {
+                continue;
+            }
+
+            let value = eval_complex(p, z[k]);
+            if let Some(residual) = residuals.get_mut(k) {
+                *residual = value.norm();
+            }
+            if value.norm() <= error_bound(z[k]) {
+                converged[k] = true;
+                continue;
+            }
+
+            let denom: Complex64 = (0..n).filter(|&j| j != k).map(|j| z[k] - z[j]).product();
+            z[k] -= value / denom;
+        }
+
+        if let Some(progress) = progress.as_deref_mut() {
+            progress(&IterationState {
+                iteration,
+                estimates: &z,
+                residuals: &residuals,
+            });
+        }
+
+        if converged.iter().all(|&c| c) {
+            return (z, true);
+        }
+    }
+
+    (z, false)
+}
+
+pub(crate) fn jenkins_traub_roots(p: &Polynomial) -> Vec<ComplexRoot> {
+    if p.grade() < 1 {
+        return vec![];
+    }
+
+    let (iterates, _) = jenkins_traub(&p.monic());
+    let derivative = p.derivative();
+    let mut roots: Vec<_> = cluster_roots(&iterates, MULTIPLICITY_TOLERANCE)
+        .into_iter()
+        .map(|(z, multiplicity)| ComplexRoot {
+            value: match multiplicity {
+                1 => polish_complex_root(p, &derivative, z),
+                _ => z,
+            },
+            multiplicity,
+        })
+        .collect();
+    roots.sort_by(|a, b| {
+        (a.value.re.total_cmp(&b.value.re)).then(a.value.im.total_cmp(&b.value.im))
+    });
+
+    roots
+}
+
+/// Finds the roots of the monic `p` one at a time, deflating each out before finding the next.
+/// Each root is found by the complex (CPOLY) variant of the Jenkins–Traub iteration.
+fn jenkins_traub(p: &Polynomial) -> (Vec<Complex64>, bool) {
+    let mut a: Vec<Complex64> = p.iter().map(|(_, c)| c.into()).collect();
+    let mut roots = vec![];
+    let mut did_converge = true;
+
+    while a.len() > 2 {
+        if a[0] == Complex64::ZERO {
+            roots.push(Complex64::ZERO);
+            a.remove(0);
+            continue;
+        }
+
+        let root = jenkins_traub_root(&a).unwrap_or_else(|estimate| {
+            did_converge = false;
+            estimate
+        });
+        roots.push(root);
+        a = div_linear(&a, root).0;
+    }
+    if let [c, _] = a[..] {
+        roots.push(-c);
+    }
+
+    (roots, did_converge)
+}
+
+fn jenkins_traub_root(p: &[Complex64]) -> Result<Complex64, Complex64> {
+    let n = p.len() - 1;
+    // H starts as p' / n, which is monic like p. The recurrence keeps it monic.
+    let mut h: Vec<_> = p
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(i, c)| c * (i as f64 / n as f64))
+        .collect();
+
+    // Stage 1: no shift, to accentuate the smallest roots in H.
+    let p0 = p[0];
+    for _ in 0..NO_SHIFT_ITERATIONS {
+        let step = shift_step(p0, &h, Complex64::ZERO);
+        h = next_h(p, &h, Complex64::ZERO, step);
+    }
+
+    // Stage 2: fixed shifts on the circle holding the smallest root, rotated after each failure.
+    let radius = cauchy_lower_bound(p);
+    let rotation = Complex64::from_polar(1., 94f64.to_radians());
+    let mut direction = Complex64::from_polar(1., -45f64.to_radians());
+    let mut estimate = Complex64::ZERO;
+
+    for shift in 1..=FIXED_SHIFTS {
+        direction *= rotation;
+        match fixed_shift(p, &mut h, radius * direction, 10 * shift) {
+            Ok(root) => return Ok(root),
+            Err(z) => estimate = z,
+        }
+    }
+
+    Err(estimate)
+}
+
+fn fixed_shift(
+    p: &[Complex64],
+    h: &mut Vec<Complex64>,
+    s: Complex64,
+    iterations: usize,
+) -> Result<Complex64, Complex64> {
+    let ps = eval_coefficients(p, s);
+    let mut step = shift_step(ps, h, s);
+    let mut z = s;
+    let mut passed = false;
+
+    for _ in 0..iterations {
+        let old_step = step;
+        *h = next_h(p, h, s, step);
+        step = shift_step(ps, h, s);
+
+        let (Some(new), Some(old)) = (step, old_step) else {
+            passed = false;
+            continue;
+        };
+        z = s + new;
+
+        // Once the estimates settle twice in a row, stage 3 is likely to converge.
+        if (new - old).norm() < 0.5 * z.norm() {
+            if passed {
+                if let Ok(root) = variable_shift(p, &mut h.clone(), z) {
+                    return Ok(root);
+                }
+            }
+            passed = !passed;
+        } else {
+            passed = false;
+        }
+    }
+
+    variable_shift(p, h, z)
+}
+
+// Stage 3: shifting by the current estimate converges quadratically, like Newton's method.
+fn variable_shift(
+    p: &[Complex64],
+    h: &mut Vec<Complex64>,
+    mut s: Complex64,
+) -> Result<Complex64, Complex64> {
+    let n = p.len() - 1;
+
+    for _ in 0..VARIABLE_SHIFT_ITERATIONS {
+        let ps = eval_coefficients(p, s);
+        let r = s.norm();
+        let bound = p.iter().rev().fold(0., |acc, c| acc * r + c.norm());
+        if ps.norm() <= 4. * n as f64 * f64::EPSILON * bound {
+            return Ok(s);
+        }
+
+        let step = shift_step(ps, h, s);
+        *h = next_h(p, h, s, step);
+        if let Some(step) = shift_step(ps, h, s) {
+            s += step;
+        }
+    }
+
+    Err(s)
+}
+
+/// `-p(s) / H(s)`, or `None` when `H(s)` is too close to 0 to divide by.
+fn shift_step(ps: Complex64, h: &[Complex64], s: Complex64) -> Option<Complex64> {
+    let hs = eval_coefficients(h, s);
+    let scale = h.iter().map(|c| c.norm()).fold(0., f64::max);
+    (hs.norm() > 10. * f64::EPSILON * scale).then(|| -ps / hs)
+}
+
+/// The next H polynomial, `(H(z) + step * p(z)) / (z - s)` scaled to stay monic.
+fn next_h(
+    p: &[Complex64],
+    h: &[Complex64],
+    s: Complex64,
+    step: Option<Complex64>,
+) -> Vec<Complex64> {
+    let qh = div_linear(h, s).0;
+    let Some(step) = step else {
+        // H(s) is 0, so H is divisible by z - s; the leading coefficient becomes 0.
+        let mut next = qh;
+        next.push(Complex64::ZERO);
+        return next;
+    };
+
+    let qp = div_linear(p, s).0;
+    qp.iter()
+        .enumerate()
+        .map(|(i, &c)| c + qh.get(i).map_or(Complex64::ZERO, |&q| step * q))
+        .collect()
+}
+
+/// The unique positive root of `|a_n|x^n + … + |a_1|x - |a_0|`, a lower bound on the moduli of
+/// the roots of `p`.
+fn cauchy_lower_bound(p: &[Complex64]) -> f64 {
+    let n = p.len() - 1;
+    let abs: Vec<_> = p.iter().map(|c| c.norm()).collect();
+    let f = |x: f64| abs[1..].iter().rev().fold(0., |acc, c| acc * x + c) * x - abs[0];
+    let df = |x: f64| {
+        abs.iter()
+            .enumerate()
+            .skip(1)
+            .rev()
+            .fold(0., |acc, (i, c)| acc * x + i as f64 * c)
+    };
+
+    // Both starting points make a term equal to |a_0|, so f is not negative there.
+    let mut x = (abs[0] / abs[n]).powf(1. / n as f64);
+    if abs[1] != 0. {
+        x = x.min(abs[0] / abs[1]);
+    }
+    while f(x * 0.1) > 0. {
+        x *= 0.1;
+    }
+
+    // f is convex and increasing, so Newton's method approaches the root from the right.
+    for _ in 0..MAX_POLISH_ITERATIONS {
+        let dx = f(x) / df(x);
+        x -= dx;
+        if dx.abs() <= 0.005 * x {
+            break;
+        }
+    }
+
+    x
+}
+
+/// Divides by `z - s`, returning the quotient and the remainder `a(s)`.
+fn div_linear(a: &[Complex64], s: Complex64) -> (Vec<Complex64>, Complex64) {
+    let mut quotient = vec![Complex64::ZERO; a.len().saturating_sub(1)];
+    let mut acc = Complex64::ZERO;
+
+    for (i, &c) in a.iter().enumerate().rev() {
+        acc = acc * s + c;
+        if i > 0 {
+            quotient[i - 1] = acc;
+        }
+    }
+
+    (quotient, acc)
+}
+
+fn eval_coefficients(a: &[Complex64], z: Complex64) -> Complex64 {
+    a.iter().rev().fold(Complex64::ZERO, |acc, &c| acc * z + c)
+}
+
+// Iterates converging to a root of multiplicity m are spread around it, so nearby
+// iterates are merged into a single root whose multiplicity is the cluster size.
+fn cluster_roots(iterates: &[Complex64], tolerance: f64) -> Vec<(Complex64, i32)> {
+    let mut clusters: Vec<(Complex64, i32)> = vec![];
+
+    for &z in iterates {
+        let near = clusters.iter_mut().find(|(c, m)| {
+            let center = c / *m as f64;
+            (center - z).norm() <= tolerance * center.norm().max(1.)
+        });
+
+        match near {
+            Some((sum, m)) => {
+                *sum += z;
+                *m += 1;
+            }
+            None => clusters.push((z, 1)),
+        }
+    }
+
+    clusters
+        .into_iter()
+        .map(|(sum, m)| (sum / m as f64, m))
+        .collect()
+}
+
+fn eval_complex(p: &Polynomial, z: Complex64) -> Complex64 {
+    let (re, im) = p.eval_complex(z.re, z.im);
+    Complex64::new(re, im)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn random_polynomial(seed: u64, grade: usize) -> Polynomial {
+        use rand::{Rng, SeedableRng};
+
+        rand::rngs::StdRng::seed_from_u64(seed)
+            .sample_iter(rand::distributions::Uniform::from(-1.0..1.0))
+            .take(grade + 1)
+            .collect::<Vec<_>>()
+            .into()
+    }
+
+    fn roots(p: &[f64]) -> Vec<(f64, i32)> {
+        let mut roots = find_roots(&p.to_vec().into()).unwrap();
+        sort_roots(&mut roots);
+        roots
+            .into_iter()
+            .map(|r| (r.value, r.multiplicity))
+            .collect()
+    }
+
+    #[test]
+    fn test_sort_roots() {
+        let root = |value, multiplicity| Root {
+            value,
+            multiplicity,
+        };
+
+        let mut roots = [root(2., 1), root(-1., 2), root(0.5, 1), root(-1., 1)];
+        sort_roots(&mut roots);
+        assert_eq!(
+            roots,
+            [root(-1., 1), root(-1., 2), root(0.5, 1), root(2., 1)]
+        );
+
+        let mut roots = [root(f64::NAN, 1), root(1., 1)];
+        sort_roots(&mut roots);
+        assert_eq!(roots[0], root(1., 1));
+    }
+
+    #[test]
+    fn test_quadratic_cancellation() {
+        let r = roots(&[1., -1e8, 1.]);
+        assert_eq!(r.len(), 2);
+        assert!((r[0].0 - 1e-8).abs() <= 1e-8 * f64::EPSILON * 4.);
+        assert_eq!(r[1].0, 1e8);
+
+        assert_eq!(roots(&[-2., 1., 1.]), [(-2., 1), (1., 1)]);
+        assert_eq!(roots(&[-4., 0., 1.]), [(-2., 1), (2., 1)]);
+        assert_eq!(roots(&[4., 0., -1.]), [(-2., 1), (2., 1)]);
+    }
+
+    #[test]
+    fn test_trailing_zeros() {
+        let p: Polynomial = [5., 0.].into();
+        assert_eq!(p.grade(), 0);
+        assert_eq!(find_roots(&p).unwrap(), []);
+
+        assert_eq!(roots(&[5., 0., 0., 0.]), []);
+        assert_eq!(roots(&[-2., 1., 0.]), [(2., 1)]);
+        assert!(find_roots(&[0., 0., 0.].into()).is_none());
+    }
+
+    #[test]
+    fn test_biquadratic() {
+        assert_eq!(
+            roots(&[4., 0., -5., 0., 1.]),
+            [(-2., 1), (-1., 1), (1., 1), (2., 1)]
+        );
+        assert_eq!(roots(&[0., 0., 0., 0., 1.]), [(0., 4)]);
+        assert_eq!(roots(&[0., 0., -1., 0., 1.]), [(-1., 1), (0., 2), (1., 1)]);
+        assert_eq!(roots(&[1., 0., -2., 0., 1.]), [(-1., 2), (1., 2)]);
+        assert_eq!(roots(&[1., 0., 1., 0., 1.]), []);
+    }
+
+    #[test]
+    fn test_even_odd() {
+        let sqrt2 = 2f64.sqrt();
+        assert_roots_near(
+            &roots(&[-8., 0., 14., 0., -7., 0., 1.]),
+            &[
+                (-2., 1),
+                (-sqrt2, 1),
+                (-1., 1),
+                (1., 1),
+                (sqrt2, 1),
+                (2., 1),
+            ],
+            1e-12,
+        );
+        assert_eq!(
+            roots(&[0., 4., 0., -5., 0., 1.]),
+            [(-2., 1), (-1., 1), (0., 1), (1., 1), (2., 1)]
+        );
+        assert_eq!(roots(&[0., 0., 0., 0., 0., 1.]), [(0., 5)]);
+    }
+
+    #[test]
+    fn test_zero_root() {
+        assert_eq!(roots(&[0., 0., -1., 1.]), [(0., 2), (1., 1)]);
+        assert_eq!(roots(&[0., 0., 0., 2., 0., 1.]), [(0., 3)]);
+
+        let p: Polynomial = [0., 0., -6., 11., -6., 1.].into();
+        let mut trace = Trace::enabled();
+        let mut solution = RootFinder::default().solve_traced(&p, &mut trace).unwrap();
+        sort_roots(&mut solution.roots);
+        assert!(trace.steps()[0].contains("0 is a root of multiplicity 2"));
+        assert_eq!(
+            solution.roots[0],
+            Root {
+                value: 0.,
+                multiplicity: 2
+            }
+        );
+        assert_eq!(solution.roots.len(), 4);
+    }
+
+    #[test]
+    fn test_from_roots_round_trip() {
+        use rand::Rng;
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let mut expected = vec![(rng.gen_range(-10..10) as f64, 1)];
+            let other = rng.gen_range(-10..10) as f64;
+            if other == expected[0].0 {
+                expected[0].1 = 2;
+            } else {
+                expected.push((other, 1));
+            }
+            expected.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+            let p = Polynomial::from_roots(&expected);
+            assert_eq!(
+                roots(&p.iter().map(|(_, v)| v).collect::<Vec<_>>()),
+                expected
+            );
+        }
+    }
+
+    fn assert_roots_near(actual: &[(f64, i32)], expected: &[(f64, i32)], tol: f64) {
+        assert_eq!(actual.len(), expected.len(), "{actual:?} != {expected:?}");
+        for (a, e) in actual.iter().zip(expected) {
+            assert!(
+                (a.0 - e.0).abs() < tol && a.1 == e.1,
+                "{actual:?} != {expected:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_approximation_multiplicities() {
+        let p = Polynomial::from_roots(&[(2., 3), (-1., 1)]);
+        let actual = roots(&p.iter().map(|(_, v)| v).collect::<Vec<_>>());
+        assert_roots_near(&actual, &[(-1., 1), (2., 3)], 1e-4);
+
+        let p = Polynomial::from_roots(&[(1., 1), (2., 1), (3., 1)]);
+        let actual = roots(&p.iter().map(|(_, v)| v).collect::<Vec<_>>());
+        assert_roots_near(&actual, &[(1., 1), (2., 1), (3., 1)], 1e-12);
+
+        let p = Polynomial::from_roots(&[(1., 1)]) * [1., 1., 1.].into();
+        let actual = roots(&p.iter().map(|(_, v)| v).collect::<Vec<_>>());
+        assert_roots_near(&actual, &[(1., 1)], 1e-12);
+    }
+
+    #[test]
+    fn test_complex_roots() {
+        let roots = find_complex_roots(&[1., 0., 1.].into()).unwrap();
+        assert_eq!(roots.len(), 2);
+        assert_eq!(roots[0].value.re, 0.);
+        assert!((roots[0].value.im - 1.).abs() < 1e-12);
+        assert_eq!(roots[1].value, roots[0].value.conj());
+
+        // (x - 2)(x^2 - 2x + 5) has roots 2 and 1 ± 2i.
+        let roots = find_complex_roots(&[-10., 9., -4., 1.].into()).unwrap();
+        assert_eq!(roots.len(), 2);
+        assert!((roots[0].value - Complex64::new(1., 2.)).norm() < 1e-12);
+
+        assert_eq!(find_complex_roots(&[1., 2., 1.].into()).unwrap(), []);
+        assert!(find_complex_roots(&Polynomial::ZERO).is_none());
+    }
+
+    #[test]
+    fn test_halley_polishing() {
+        let p = Polynomial::from_roots(&[(3., 2)]);
+
+        let newton = polish(&p, 4., |x| newton_step(&p, x));
+        let halley = polish(&p, 4., |x| halley_step(&p, x));
+        assert!(halley.1 < newton.1);
+        assert!((halley.0 - 3.).abs() <= (newton.0 - 3.).abs());
+
+        let finder = RootFinder {
+            polishing: Polishing::Halley,
+            ..Default::default()
+        };
+        let p = Polynomial::from_roots(&[(-1.5, 1), (0.5, 1), (2., 1), (7., 1)]);
+        let mut roots = finder.solve(&p).unwrap().roots;
+        sort_roots(&mut roots);
+        assert_eq!(
+            roots.iter().map(|r| r.value).collect::<Vec<_>>(),
+            [-1.5, 0.5, 2., 7.]
+        );
+    }
+
+    #[test]
+    fn test_householder_polishing() {
+        let p = Polynomial::from_roots(&[(1., 1), (-2., 1), (5., 1)]);
+        let householder = |order: usize, x| {
+            householder_step(&p, &mut vec![0.; order + 1], &mut vec![0.; order + 1], x)
+        };
+
+        for x in [-3., 0., 1.4, 4.5] {
+            let newton = newton_step(&p, x).unwrap();
+            let order_1 = householder(1, x).unwrap();
+            assert!(
+                (order_1 - newton).abs() <= 1e-12 * newton.abs(),
+                "{order_1} != {newton}"
+            );
+
+            let halley = halley_step(&p, x).unwrap();
+            let order_2 = householder(2, x).unwrap();
+            assert!(
+                (order_2 - halley).abs() <= 1e-12 * halley.abs(),
+                "{order_2} != {halley}"
+            );
+        }
+
+        let newton = polish(&p, 1.4, |x| newton_step(&p, x));
+        let order_3 = polish(&p, 1.4, |x| householder(3, x));
+        assert!(order_3.1 < newton.1);
+        assert!((order_3.0 - 1.).abs() <= f64::EPSILON);
+
+        let finder = RootFinder {
+            polishing: Polishing::Householder(4),
+            ..Default::default()
+        };
+        let p = Polynomial::from_roots(&[(-1.5, 1), (0.5, 1), (2., 1), (7., 1)]);
+        let mut roots = finder.solve(&p).unwrap().roots;
+        sort_roots(&mut roots);
+        assert_eq!(
+            roots.iter().map(|r| r.value).collect::<Vec<_>>(),
+            [-1.5, 0.5, 2., 7.]
+        );
+    }
+
+    #[test]
+    fn test_all_roots_eig() {
+        // (x - 1)(x + 2)(x - 0.5)(x^2 + 2x + 5)
+        let p =
+            Polynomial::from_roots(&[(1., 1), (-2., 1), (0.5, 1)]) * Polynomial::from([5., 2., 1.]);
+        let roots = p.all_roots_eig().unwrap();
+
+        let expected = [
+            Complex64::new(-2., 0.),
+            Complex64::new(-1., -2.),
+            Complex64::new(-1., 2.),
+            Complex64::new(0.5, 0.),
+            Complex64::new(1., 0.),
+        ];
+        assert_eq!(roots.len(), expected.len());
+        for (root, expected) in roots.iter().zip(expected) {
+            assert!((root.value - expected).norm() < 1e-12, "{root:?}");
+            assert_eq!(root.multiplicity, 1);
+        }
+        assert!(roots.iter().filter(|r| r.value.im == 0.).count() == 3);
+
+        let roots = Polynomial::from_roots(&[(2., 2), (-1., 1)])
+            .all_roots_eig()
+            .unwrap();
+        assert_eq!(
+            roots.iter().map(|r| r.multiplicity).collect::<Vec<_>>(),
+            [1, 2]
+        );
+        assert_eq!(Polynomial::from([3.]).all_roots_eig().unwrap(), []);
+        assert!(Polynomial::ZERO.all_roots_eig().is_none());
+    }
+
+    #[test]
+    fn test_companion_balanced() {
+        let roots = [1e-5, 1e-3, 1., 1e3, 1e5];
+        let p = Polynomial::from_roots(&roots.map(|r| (r, 1)));
+
+        let b = p.companion_balanced().unwrap();
+        assert_eq!(b.len(), 5);
+        assert!(b.iter().all(|row| row.len() == 5));
+
+        let relative_error = |a: Vec<Vec<f64>>| {
+            let eigenvalues = hessenberg_eigenvalues(a).unwrap();
+            roots
+                .iter()
+                .map(|&r| {
+                    eigenvalues
+                        .iter()
+                        .map(|z| (z - r).norm() / r)
+                        .fold(f64::MAX, f64::min)
+                })
+                .fold(0., f64::max)
+        };
+
+        let a = companion_matrix(&p).unwrap();
+        let mut balanced = a.clone();
+        balance(&mut balanced);
+        assert!((1..=5).all(|i| balanced[i][1..] == b[i - 1][..]));
+
+        let unbalanced = relative_error(a);
+        let balanced = relative_error(balanced);
+        assert!(balanced < 1e-12, "{balanced}");
+        assert!(balanced * 100. < unbalanced, "{balanced} {unbalanced}");
+
+        assert_eq!(Polynomial::from([]).companion_balanced(), None);
+    }
+
+    #[test]
+    fn test_multiplicity_tolerance() {
+        let p = Polynomial::from_roots(&[(1., 1), (1.0001, 1), (3., 1)]);
+        let solve = |multiplicity_tolerance| {
+            let finder = RootFinder {
+                multiplicity_tolerance,
+                ..Default::default()
+            };
+            let mut roots = finder.solve(&p).unwrap().roots;
+            sort_roots(&mut roots);
+            roots
+                .into_iter()
+                .map(|r| (r.value, r.multiplicity))
+                .collect::<Vec<_>>()
+        };
+
+        assert_roots_near(&solve(1e-8), &[(1., 1), (1.0001, 1), (3., 1)], 1e-10);
+        assert_roots_near(&solve(1e-2), &[(1.00005, 2), (3., 1)], 1e-6);
+    }
+
+    #[test]
+    fn test_odd_palindrome() {
+        let p = Polynomial::from_roots(&[(-1., 1), (2., 1), (0.5, 1)])
+            * Polynomial::from([1., 3., 1.])
+            * Polynomial::from([1., -3., 1.]);
+        assert_eq!(p.grade(), 7);
+        assert!(p.is_palindrome());
+
+        let sqrt5 = 5f64.sqrt();
+        let mut expected = vec![
+            (-1., 1),
+            (2., 1),
+            (0.5, 1),
+            ((-3. - sqrt5) / 2., 1),
+            ((-3. + sqrt5) / 2., 1),
+            ((3. - sqrt5) / 2., 1),
+            ((3. + sqrt5) / 2., 1),
+        ];
+        expected.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let coefs: Vec<_> = p.iter().map(|(_, v)| v).collect();
+        assert_roots_near(&roots(&coefs), &expected, 1e-12);
+
+        let mut trace = Trace::enabled();
+        RootFinder::default().solve_traced(&p, &mut trace);
+        assert!(trace.steps()[0].starts_with("odd palindrome"));
+        assert!(trace.steps()[1].starts_with("even palindrome"));
+    }
+
+    #[test]
+    fn test_trace() {
+        let p: Polynomial = [4., 0., -5., 0., 1.].into();
+
+        let mut trace = Trace::enabled();
+        RootFinder::default().solve_traced(&p, &mut trace);
+        assert!(trace.steps()[0].starts_with("biquadratic"));
+
+        let mut trace = Trace::default();
+        RootFinder::default().solve_traced(&p, &mut trace);
+        assert!(trace.steps().is_empty());
+    }
+
+    #[test]
+    fn test_iteration_cap() {
+        let p = Polynomial::from_roots(&[(1.1, 1), (2.1, 1), (3.1, 1), (4.1, 1), (5.1, 1)]);
+
+        let solution = RootFinder::default().solve(&p).unwrap();
+        assert!(solution.did_converge);
+        assert_eq!(solution.roots.len(), 5);
+
+        let finder = RootFinder {
+            max_iterations: 1,
+            ..Default::default()
+        };
+        let mut trace = Trace::enabled();
+        let solution = finder.solve_traced(&p, &mut trace).unwrap();
+        assert!(!solution.did_converge);
+        assert!(trace.steps().last().unwrap().contains("without converging"));
+
+        let solution = finder.solve(&[-2., 1.].into()).unwrap();
+        assert!(solution.did_converge);
+    }
+
+    #[test]
+    fn test_find_roots_checked() {
+        let p = Polynomial::from([0., 0., 0., f64::MAX]).derivative();
+        assert_eq!(
+            find_roots_checked(&p).unwrap_err(),
+            Error::NonFinite { index: 2 }
+        );
+
+        let roots = find_roots_checked(&[-2., 1.].into()).unwrap().unwrap();
+        assert_eq!(roots.len(), 1);
+        assert_eq!(find_roots_checked(&Polynomial::ZERO), Ok(None));
+    }
+
+    #[test]
+    fn test_reciprocal_roots() {
+        let expected = [-3e6 * 3f64.sqrt(), 1e6 * 2f64.sqrt(), 2e6 * 5f64.sqrt()];
+        let p = Polynomial::from_roots(&expected.map(|r| (r, 1)));
+        let mut trace = Trace::enabled();
+        let mut roots = RootFinder::default()
+            .solve_traced(&p, &mut trace)
+            .unwrap()
+            .roots;
+        sort_roots(&mut roots);
+
+        assert!(trace.steps()[0].contains("reciprocal"));
+        assert_eq!(roots.len(), 3);
+        for (root, value) in roots.iter().zip(expected) {
+            assert!((root.value - value).abs() <= 1e-9 * value.abs(), "{root:?}");
+            assert_eq!(root.multiplicity, 1);
+        }
+        assert_eq!(p.reverse_and_solve().unwrap(), roots);
+
+        // Small roots would become large ones, so the reciprocal isn't used.
+        let p = Polynomial::from_roots(&[(1e6 * 2f64.sqrt(), 1), (0.7, 1)]);
+        let mut trace = Trace::enabled();
+        RootFinder::default().solve_traced(&p, &mut trace);
+        assert!(trace.steps().iter().all(|s| !s.contains("reciprocal")));
+
+        // Scaling the reciprocal by the root bound would overflow, so it's solved directly.
+        let expected: Vec<_> = (0..60)
+            .map(|k| if k % 2 == 0 { 1. } else { -1. } * (2e4 + 200. * k as f64))
+            .collect();
+        let p = Polynomial::from_roots(&expected.iter().map(|&r| (r, 1)).collect::<Vec<_>>());
+        assert!(p.iter().all(|(_, v)| v.is_finite()));
+        let mut trace = Trace::enabled();
+        RootFinder::default().solve_traced(&p, &mut trace).unwrap();
+        assert!(trace.steps().iter().all(|s| !s.contains("reciprocal")));
+        assert_eq!(p.reverse_and_solve(), None);
+
+        assert_eq!(Polynomial::from([0., 1.]).reverse_and_solve(), None);
+        assert_eq!(Polynomial::ZERO.reverse_and_solve(), None);
+    }
+
+    #[test]
+    fn test_repeated_roots() {
+        // (x^2 - 2)^3 (x^2 + x - 1)^2, without rational roots.
+        let p = Polynomial::from([-2., 0., 1.]).pow(3) * Polynomial::from([-1., 1., 1.]).pow(2);
+        let mut trace = Trace::enabled();
+        let mut roots = RootFinder::default()
+            .solve_traced(&p, &mut trace)
+            .unwrap()
+            .roots;
+        sort_roots(&mut roots);
+
+        let (sqrt2, sqrt5) = (2f64.sqrt(), 5f64.sqrt());
+        let expected = [
+            ((-1. - sqrt5) / 2., 2),
+            (-sqrt2, 3),
+            ((-1. + sqrt5) / 2., 2),
+            (sqrt2, 3),
+        ];
+        assert_eq!(roots.len(), expected.len());
+        for (root, (value, multiplicity)) in roots.iter().zip(expected) {
+            assert!((root.value - value).abs() < 1e-12, "{root:?}");
+            assert_eq!(root.multiplicity, multiplicity);
+        }
+        assert!(trace.steps()[0].contains("repeated roots"));
+        assert!(trace.steps().iter().all(|s| !s.contains("approximating")));
+    }
+
+    #[test]
+    fn test_jenkins_traub() {
+        let check = |expected: &[Complex64]| {
+            let p: Polynomial = expected.iter().map(|z| (z.re, z.im)).fold(
+                Polynomial::from([1.]),
+                |acc, (re, im)| match im {
+                    0. => acc * Polynomial::from([-re, 1.]),
+                    // Each pair is listed once, by its upper root.
+                    _ => acc * Polynomial::from([re * re + im * im, -2. * re, 1.]),
+                },
+            );
+            let roots = p.jenkins_traub();
+            assert!(roots.iter().all(|r| r.multiplicity == 1));
+            assert_eq!(
+                roots.len(),
+                expected
+                    .iter()
+                    .map(|z| if z.im == 0. { 1 } else { 2 })
+                    .sum()
+            );
+            for z in expected.iter().flat_map(|&z| [z, z.conj()]) {
+                assert!(
+                    roots
+                        .iter()
+                        .any(|r| (r.value - z).norm() <= 1e-9 * z.norm().max(1.)),
+                    "{z} not in {roots:?}"
+                );
+            }
+        };
+
+        let c = Complex64::new;
+        // Widely separated.
+        check(&[c(1e-3, 0.), c(1., 0.), c(-1e3, 0.), c(2., 5e2)]);
+        // Clustered.
+        check(&[
+            c(1., 0.),
+            c(1.01, 0.),
+            c(1.02, 0.),
+            c(1.03, 0.1),
+            c(-3., 0.),
+        ]);
+        check(&[c(0., 1.), c(-0.5, 2.), c(4., 0.), c(0., 0.)]);
+
+        let finder = RootFinder {
+            strategy: Strategy::JenkinsTraub,
+            ..Default::default()
+        };
+        let p = Polynomial::from_roots(&[(-2., 1), (0.5, 1), (3., 1), (7., 1)]);
+        let solution = finder.solve(&(&p * &[1., 1., 1.].into())).unwrap();
+        assert!(solution.did_converge);
+        let mut roots = solution.roots;
+        sort_roots(&mut roots);
+        for (root, r) in roots.iter().zip([-2., 0.5, 3., 7.]) {
+            assert!((root.value - r).abs() < 1e-12, "{} != {r}", root.value);
+        }
+
+        assert!(Polynomial::from([3.]).jenkins_traub().is_empty());
+    }
+
+    #[test]
+    fn test_find_roots_iter() {
+        let p = Polynomial::from_roots(&[(4., 1), (0.5, 1), (-8., 1), (2., 1), (-1., 1)]);
+        let first: Vec<_> = find_roots_iter(&p).take(2).map(|r| r.value).collect();
+        assert_eq!(first.len(), 2);
+        assert!((first[0] - 0.5).abs() < 1e-12);
+        assert!((first[1] + 1.).abs() < 1e-12);
+
+        assert_eq!(find_roots_iter(&p).count(), 5);
+        assert_eq!(find_roots_iter(&Polynomial::ZERO).count(), 0);
+
+        let p = Polynomial::from_roots(&[(5., 1), (1., 2), (-3., 1)]);
+        let roots: Vec<_> = find_roots_iter(&p).collect();
+        assert_eq!(roots.len(), 3);
+        for (root, (r, m)) in roots.iter().zip([(1., 2), (-3., 1), (5., 1)]) {
+            assert!((root.value - r).abs() < 1e-9, "{} != {r}", root.value);
+            assert_eq!(root.multiplicity, m);
+        }
+
+        // The magnitudes of whole isolating intervals don't decide the order, the roots' do.
+        for roots in [[(2.9, 1), (-1.9, 1)], [(-2.9, 1), (1.9, 1)]] {
+            let p = Polynomial::from_roots(&roots);
+            let first = find_roots_iter(&p).next().unwrap();
+            assert!((first.value - roots[1].0).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_bisection() {
+        use core::f64::consts::SQRT_2;
+
+        let roots = find_roots_by_bisection(&[-2., 0., 1.].into()).unwrap();
+        let values: Vec<_> = roots.iter().map(|r| r.value).collect();
+        assert_eq!(values.len(), 2);
+        assert!((values[0] + SQRT_2).abs() <= 2. * f64::EPSILON);
+        assert!((values[1] - SQRT_2).abs() <= 2. * f64::EPSILON);
+
+        let p = Polynomial::from_roots(&[(-1., 2), (0.5, 1), (3., 3)]);
+        let roots = find_roots_by_bisection(&p).unwrap();
+        assert_eq!(
+            roots,
+            [(-1., 2), (0.5, 1), (3., 3)].map(|(value, multiplicity)| Root {
+                value,
+                multiplicity
+            })
+        );
+
+        assert_eq!(find_roots_by_bisection(&[1., 0., 1.].into()), Some(vec![]));
+        assert_eq!(find_roots_by_bisection(&Polynomial::ZERO), None);
+    }
+
+    #[test]
+    fn test_deflation() {
+        let expected = [-7., -3.5, -1., -0.25, 0.5, 2., 3., 10.];
+        let p = Polynomial::from_roots(&expected.map(|r| (r, 1)));
+
+        let roots = p.solve_by_deflation();
+        assert_eq!(roots.len(), expected.len());
+        for (root, r) in roots.iter().zip(expected) {
+            assert!((root.value - r).abs() < 1e-9, "{} != {r}", root.value);
+            assert_eq!(root.multiplicity, 1);
+        }
+
+        let finder = RootFinder {
+            strategy: Strategy::Deflation,
+            ..Default::default()
+        };
+        let p = Polynomial::from_roots(&[(-2., 2), (1., 1), (3., 1), (5., 3)]);
+        let mut roots = finder.solve(&p).unwrap().roots;
+        sort_roots(&mut roots);
+        let multiplicities: Vec<_> = roots.iter().map(|r| r.multiplicity).collect();
+        assert_eq!(multiplicities, [2, 1, 1, 3]);
+        for (root, r) in roots.iter().zip([-2., 1., 3., 5.]) {
+            assert!((root.value - r).abs() < 1e-9, "{} != {r}", root.value);
+        }
+    }
+
+    #[test]
+    fn test_seed() {
+        let p = Polynomial::from_roots(&[(1., 1), (2., 1), (3., 1), (4., 1), (5., 1)]);
+        let solve = |finder: RootFinder| {
+            let mut roots = finder.solve(&p).unwrap().roots;
+            sort_roots(&mut roots);
+            roots
+        };
+
+        let seeded = RootFinder::default().with_seed(42);
+        assert_eq!(seeded.seed, Some(42));
+        assert_eq!(solve(seeded.clone()), solve(seeded));
+        assert_eq!(solve(RootFinder::default()), solve(RootFinder::default()));
+
+        for seed in 0..5 {
+            let roots = solve(RootFinder::default().with_seed(seed));
+            assert_eq!(roots.len(), 5);
+            for (root, r) in roots.iter().zip(1..) {
+                assert!((root.value - r as f64).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn test_extreme_magnitudes() {
+        let p = Polynomial::from([0.5, -3.1, 0., 0., 1e21, 1.]);
+        let roots = find_roots(&p).unwrap();
+        assert!(roots.iter().any(|r| (r.value + 1e21).abs() < 1e6));
+
+        let expected: Vec<_> = (0..20)
+            .map(|k| if k % 2 == 0 { 1. } else { -1. } * (2e4 + 400. * k as f64))
+            .collect();
+        let p = Polynomial::from_roots(&expected.iter().map(|&r| (r, 1)).collect::<Vec<_>>());
+        let roots = find_roots(&p).unwrap();
+        assert_eq!(roots.len(), 20);
+        assert!(RootFinder::default().solve_complex(&p).is_some());
+    }
+
+    #[test]
+    fn test_rational_roots() {
+        let roots = find_roots(&[1., -5., 6.].into()).unwrap();
+        assert_eq!(
+            roots.iter().map(|r| r.value).collect::<Vec<_>>(),
+            [1. / 3., 0.5]
+        );
+
+        // (2x - 1)(3x - 1)^2(5x + 2)(x^2 - 2)
+        let p = Polynomial::from([-1., 2.])
+            * Polynomial::from([-1., 3.]).pow(2)
+            * Polynomial::from([2., 5.])
+            * Polynomial::from([-2., 0., 1.]);
+        let mut trace = Trace::enabled();
+        let mut roots = RootFinder::default()
+            .solve_traced(&p, &mut trace)
+            .unwrap()
+            .roots;
+        sort_roots(&mut roots);
+
+        let sqrt2 = 2f64.sqrt();
+        assert_eq!(roots.len(), 5);
+        assert!((roots[0].value + sqrt2).abs() < 1e-12);
+        assert!((roots[4].value - sqrt2).abs() < 1e-12);
+        assert_eq!(
+            roots[1..4],
+            [(-0.4, 1), (1. / 3., 2), (0.5, 1)].map(|(value, multiplicity)| Root {
+                value,
+                multiplicity
+            })
+        );
+        assert!(trace.steps()[0].contains("rational roots"));
+
+        assert_eq!(Polynomial::from([-2., 0., 1.]).rational_roots(), None);
+        assert_eq!(Polynomial::from([-0.1, 1.]).rational_roots(), None);
+    }
+
+    #[test]
+    fn test_tolerance() {
+        // x^3 - 8e-12, measured with some noise in the linear term.
+        let p = Polynomial::from([-8e-12, 1e-17, 0., 1.]);
+        let solve = |tolerance| {
+            let mut trace = Trace::enabled();
+            let finder = RootFinder {
+                tolerance,
+                ..Default::default()
+            };
+            let roots = finder.solve_traced(&p, &mut trace).unwrap().roots;
+            (roots, trace)
+        };
+
+        // The strict tolerance takes the noise for a real term and approximates the root.
+        let (strict, trace) = solve(TOLERANCE);
+        assert!(trace.steps().iter().all(|s| !s.contains("binomial")));
+        assert_eq!(strict.len(), 1);
+        assert!((strict[0].value - 2e-4).abs() < 1e-12);
+
+        let (loose, trace) = solve(1e-6);
+        assert!(trace.steps()[0].contains("binomial"));
+        assert_eq!(loose.len(), 1);
+        assert!((loose[0].value - 2e-4).abs() < 1e-15);
+    }
+
+    #[test]
+    fn test_tolerance_residual() {
+        // The roots ±0.001i pass for real under the loose tolerance, but 0 is no root.
+        let p = Polynomial::from_roots(&[(-3., 1), (5., 1)]) * Polynomial::from([1e-6, 0., 1.]);
+        let finder = RootFinder {
+            tolerance: 1e-6,
+            ..Default::default()
+        };
+        let mut roots = finder.solve(&p).unwrap().roots;
+        sort_roots(&mut roots);
+        assert_roots_near(
+            &roots
+                .iter()
+                .map(|r| (r.value, r.multiplicity))
+                .collect::<Vec<_>>(),
+            &[(-3., 1), (5., 1)],
+            1e-12,
+        );
+    }
+
+    #[test]
+    fn test_binomial() {
+        let check = |p: Polynomial, expected: &[f64]| {
+            let mut roots: Vec<_> = find_roots(&p).unwrap().iter().map(|r| r.value).collect();
+            roots.sort_by(f64::total_cmp);
+            assert_eq!(roots.len(), expected.len(), "{p}");
+            for (root, value) in roots.iter().zip(expected) {
+                assert!((root - value).abs() < 1e-14, "{p}: {root}");
+            }
+        };
+
+        for n in 1..=12 {
+            let mut coefficients = vec![0.; n + 1];
+            coefficients[n] = 1.;
+            coefficients[0] = -2f64.powi(n as i32);
+            let expected: &[f64] = if n % 2 == 0 { &[-2., 2.] } else { &[2.] };
+            check(coefficients.clone().into(), expected);
+
+            coefficients[0] = 2f64.powi(n as i32);
+            let expected: &[f64] = if n % 2 == 0 { &[] } else { &[-2.] };
+            check(coefficients.clone().into(), expected);
+
+            // The sign of the leading coefficient counts as much as that of the constant.
+            coefficients[n] = -1.;
+            let expected: &[f64] = if n % 2 == 0 { &[-2., 2.] } else { &[2.] };
+            check(coefficients.into(), expected);
+        }
+
+        check([-7., 0., 0., 0., 0., 1.].into(), &[7f64.powf(0.2)]);
+
+        // Even binomials are solved as binomials too, not through the substitution y = x^2.
+        for p in [[-16., 0., 0., 0., 1.], [16., 0., 0., 0., 1.]] {
+            let mut trace = Trace::enabled();
+            RootFinder::default().solve_traced(&p.into(), &mut trace);
+            assert_eq!(trace.steps().len(), 1);
+            assert!(
+                trace.steps()[0].starts_with("binomial"),
+                "{:?}",
+                trace.steps()
+            );
+        }
+    }
+
+    #[test]
+    fn test_progress_callback() {
+        let p = Polynomial::from_roots(&[(1.1, 1), (2.1, 1), (3.1, 1), (4.1, 1), (5.1, 1)]);
+
+        let finder = RootFinder {
+            max_iterations: 3,
+            ..Default::default()
+        };
+        let mut iterations = vec![];
+        finder.solve_with_progress(&p, |state| {
+            assert_eq!(state.estimates.len(), 5);
+            assert_eq!(state.residuals.len(), 5);
+            iterations.push(state.iteration);
+        });
+        assert_eq!(iterations, [0, 1, 2]);
+
+        let mut calls = 0;
+        let solution = RootFinder::default()
+            .solve_with_progress(&p, |_| calls += 1)
+            .unwrap();
+        assert!(calls > 0);
+        assert_eq!(
+            solution.roots,
+            RootFinder::default().solve(&p).unwrap().roots
+        );
+
+        calls = 0;
+        RootFinder::default().solve_with_progress(&[-2., 1.].into(), |_| calls += 1);
+        assert_eq!(calls, 0);
+    }
+
+    #[bench]
+    fn bench_solve_degree_50(b: &mut test::Bencher) {
+        let p = random_polynomial(50, 50);
+        b.iter(|| find_roots(&p))
+    }
+
+    #[bench]
+    fn bench_polish_newton_degree_50(b: &mut test::Bencher) {
+        let p = random_polynomial(50, 50);
+        let (iterates, _) = durand_kerner(&p.monic(), 500, 0.4, None);
+        b.iter(|| iterates.iter().map(|z| polish_root(&p, z.re)).sum::<f64>())
+    }
+
+    #[bench]
+    fn bench_polish_halley_degree_50(b: &mut test::Bencher) {
+        let p = random_polynomial(50, 50);
+        let (iterates, _) = durand_kerner(&p.monic(), 500, 0.4, None);
+        b.iter(|| {
+            iterates
+                .iter()
+                .map(|z| polish_root_halley(&p, z.re))
+                .sum::<f64>()
+        })
+    }
+
+    #[bench]
+    fn bench_polish_householder_degree_50(b: &mut test::Bencher) {
+        let p = random_polynomial(50, 50);
+        let (iterates, _) = durand_kerner(&p.monic(), 500, 0.4, None);
+        b.iter(|| {
+            iterates
+                .iter()
+                .map(|z| polish_root_householder(&p, 4, z.re))
+                .sum::<f64>()
+        })
+    }
 }